@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::files::project_files;
+use crate::toolchain::Toolchain;
+use crate::{Evaluation, Exercise, ExerciseOutput};
+
+// 缓存格式变化时递增，使旧的缓存全部失效
+const CACHE_VERSION: u32 = 14;
+
+// 评测结果缓存：题目源文件、工具链版本与配置都没有变化时直接复用上一次的评测结果
+pub struct Cache {
+    dir: PathBuf,
+    toolchain: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    key: String,
+    evaluation: Evaluation,
+    output: ExerciseOutput,
+}
+
+impl Cache {
+    pub fn open(dir: &Path, toolchain: &Toolchain) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        Ok(Cache {
+            dir: dir.to_path_buf(),
+            toolchain: toolchain.fingerprint(),
+        })
+    }
+
+    // 计算题目的缓存键；源文件无法读取时返回 None，此时不使用缓存
+    pub fn key(&self, exercise: &Exercise, timeout: u64, sandbox: bool, hidden: Option<&Path>) -> Option<String> {
+        let mut hasher = Sha256::new();
+        hasher.update(format!("v{} {}\n", CACHE_VERSION, env!("CARGO_PKG_VERSION")));
+        hasher.update(&self.toolchain);
+        hasher.update(serde_json::to_vec(exercise).ok()?);
+        hasher.update(timeout.to_le_bytes());
+        hasher.update([sandbox as u8]);
+
+        let root = exercise.source_path();
+        let mut files = cache_files(&root).ok()?;
+        // 题目类型依赖的其他文件（例如期望输出）排在题目文件之后
+        for input in exercise.kind.evaluator().inputs() {
+            files.extend(cache_files(&input).ok()?);
+        }
+        // 隐藏测试替换了学生的测试，同样参与计算
+        if let Some(hidden) = hidden {
+            hasher.update(b"hidden\n");
+            files.extend(cache_files(hidden).ok()?);
+        }
+        for file in files {
+            // 文件名与内容都参与计算，重命名文件同样会使缓存失效
+            hasher.update(file.strip_prefix(&root).unwrap_or(&file).to_string_lossy().as_bytes());
+            hasher.update([0]);
+            hasher.update(fs::read(&file).ok()?);
+            hasher.update([0]);
+        }
+
+        Some(hex(&hasher.finalize()))
+    }
+
+    pub fn load(&self, exercise: &Exercise, key: &str) -> Option<(Evaluation, ExerciseOutput)> {
+        let content = fs::read(self.entry_path(exercise)).ok()?;
+        let entry: CacheEntry = serde_json::from_slice(&content).ok()?;
+        (entry.key == key).then_some((entry.evaluation, entry.output))
+    }
+
+    pub fn store(&self, exercise: &Exercise, key: String, evaluation: &Evaluation, output: &ExerciseOutput) -> io::Result<()> {
+        let entry = CacheEntry {
+            key,
+            evaluation: evaluation.clone(),
+            output: output.clone(),
+        };
+        fs::write(self.entry_path(exercise), serde_json::to_vec_pretty(&entry)?)
+    }
+
+    // 每道题只保留最近一次的结果
+    fn entry_path(&self, exercise: &Exercise) -> PathBuf {
+        self.dir.join(format!("{}.json", exercise.name))
+    }
+}
+
+// 单文件题目只有一个文件；Cargo 项目包含目录下除 target 与 Cargo.lock 以外的所有文件，
+// Cargo.lock 可能在评测过程中由 cargo 生成，不能作为缓存键的一部分
+fn cache_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = project_files(path)?;
+    files.retain(|file| path.is_file() || !file.ends_with("Cargo.lock"));
+    Ok(files)
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
@@ -0,0 +1,178 @@
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::UNIX_EPOCH;
+
+use crate::history::Run;
+use crate::selection::Difficulty;
+use crate::{Exercise, ExerciseConfig, Outcome, Report, progress};
+
+// list 命令中的一道题：配置中的信息以及最近一次评测的结果
+#[derive(Serialize, Debug)]
+pub struct Entry<'a> {
+    pub name: &'a str,
+    pub difficulty: Difficulty,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+    pub points: i32,
+    // 最近一次评测的结果与得分，从未评测过时为空
+    pub last_result: Option<Outcome>,
+    pub last_score: Option<f64>,
+    // 最近一次评测的 Unix 时间戳（秒）
+    pub last_run: Option<u64>,
+    // 源文件中已经没有 `// I AM NOT DONE` 标记
+    pub done: bool,
+}
+
+// list 的排序方式，不指定时按配置文件中的顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortKey {
+    Name,
+    Difficulty,
+    Type,
+    Points,
+    Result,
+    LastRun,
+}
+
+impl FromStr for SortKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(SortKey::Name),
+            "difficulty" => Ok(SortKey::Difficulty),
+            "type" => Ok(SortKey::Type),
+            "points" => Ok(SortKey::Points),
+            "result" => Ok(SortKey::Result),
+            "last-run" => Ok(SortKey::LastRun),
+            _ => Err(format!(
+                "Unknown sort key '{}', expected one of: name, difficulty, type, points, result, last-run",
+                s
+            )),
+        }
+    }
+}
+
+// --status 的取值：最近一次评测的结果，或者从未评测过
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Graded(Outcome),
+    NotRun,
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let outcomes = [
+            Outcome::Passed,
+            Outcome::Failed,
+            Outcome::TimedOut,
+            Outcome::SandboxViolation,
+            Outcome::InProgress,
+            Outcome::EnvironmentError,
+        ];
+        if s == "not_run" {
+            return Ok(Status::NotRun);
+        }
+        match outcomes.into_iter().find(|outcome| outcome.as_str() == s) {
+            Some(outcome) => Ok(Status::Graded(outcome)),
+            None => Err(format!(
+                "Unknown status '{}', expected one of: passed, failed, timed_out, sandbox_violation, in_progress, \
+                 environment_error, not_run",
+                s
+            )),
+        }
+    }
+}
+
+impl Entry<'_> {
+    pub fn status(&self) -> Status {
+        self.last_result.map_or(Status::NotRun, Status::Graded)
+    }
+}
+
+// 按配置文件中的顺序列出选中的题目。最近一次的结果取自评测历史，
+// 历史中没有的题目再从上一次的报告中查找（此时以报告文件的修改时间作为评测时间）
+pub fn entries<'a>(
+    config: &'a ExerciseConfig,
+    exercises: &[Exercise],
+    runs: &[Run],
+    report: Option<&Path>,
+) -> Vec<Entry<'a>> {
+    let report = report.and_then(|path| {
+        let report: Report = serde_json::from_str(&fs::read_to_string(path).ok()?).ok()?;
+        let modified = fs::metadata(path).and_then(|metadata| metadata.modified()).ok()?;
+        Some((report, modified.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())))
+    });
+
+    config
+        .exercises()
+        .filter(|(_, exercise)| exercises.iter().any(|selected| selected.name == exercise.name))
+        .map(|(difficulty, exercise)| {
+            let from_history = runs.iter().rev().find_map(|run| {
+                let attempt = run.exercises.iter().find(|attempt| attempt.name == exercise.name)?;
+                Some((attempt.outcome, attempt.score, run.timestamp))
+            });
+            let last = from_history.or_else(|| {
+                let (report, modified) = report.as_ref()?;
+                let result = report.exercises.iter().find(|result| result.name == exercise.name)?;
+                Some((result.outcome, result.score, *modified))
+            });
+            Entry {
+                name: &exercise.name,
+                difficulty,
+                kind: exercise.kind.name(),
+                points: exercise.score,
+                last_result: last.map(|(outcome, _, _)| outcome),
+                last_score: last.map(|(_, score, _)| score),
+                last_run: last.map(|(_, _, timestamp)| timestamp),
+                done: !progress::not_done(&exercise.source_path()),
+            }
+        })
+        .collect()
+}
+
+// 稳定排序，相同的题目保持配置文件中的顺序（倒序时也是如此）
+pub fn sort(entries: &mut [Entry], key: SortKey, reverse: bool) {
+    entries.sort_by(|a, b| {
+        let ordering = compare(a, b, key);
+        if reverse { ordering.reverse() } else { ordering }
+    });
+}
+
+fn compare(a: &Entry, b: &Entry, key: SortKey) -> Ordering {
+    match key {
+        SortKey::Name => a.name.cmp(b.name),
+        SortKey::Difficulty => difficulty_rank(a.difficulty).cmp(&difficulty_rank(b.difficulty)),
+        SortKey::Type => a.kind.cmp(b.kind),
+        SortKey::Points => a.points.cmp(&b.points),
+        SortKey::Result => status_rank(a.status()).cmp(&status_rank(b.status())),
+        // 从未评测过的题目排在最前面
+        SortKey::LastRun => a.last_run.cmp(&b.last_run),
+    }
+}
+
+fn difficulty_rank(difficulty: Difficulty) -> u8 {
+    match difficulty {
+        Difficulty::Easy => 0,
+        Difficulty::Normal => 1,
+        Difficulty::Hard => 2,
+    }
+}
+
+// 已通过的排在最前面，其次是进行中、各种未通过，最后是从未评测过的题目
+fn status_rank(status: Status) -> u8 {
+    match status {
+        Status::Graded(Outcome::Passed) => 0,
+        Status::Graded(Outcome::InProgress) => 1,
+        Status::Graded(Outcome::Failed) => 2,
+        Status::Graded(Outcome::TimedOut) => 3,
+        Status::Graded(Outcome::SandboxViolation) => 4,
+        Status::Graded(Outcome::EnvironmentError) => 5,
+        Status::NotRun => 6,
+    }
+}
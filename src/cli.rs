@@ -0,0 +1,540 @@
+use log::LevelFilter;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::catalogue::{SortKey, Status};
+use crate::formats::Format;
+use crate::hidden;
+use crate::reset::TEMPLATES_DIR;
+use crate::selection::Selection;
+use crate::{CONFIG_FILE, DEFAULT_EXERCISES_DIR, DEFAULT_TIMEOUT_SECS, Exercise};
+
+// 命令行参数有误
+pub const EXIT_USAGE: i32 = 2;
+
+// 总分低于 --min-score
+pub const EXIT_BELOW_THRESHOLD: i32 = 3;
+
+// 评测环境有问题（工具链不可用、缺少 clippy 等），结果不能反映题目的完成情况
+pub const EXIT_ENVIRONMENT: i32 = 4;
+
+// 子命令
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    All,
+    Watch,
+    Run,
+    List,
+    Hint,
+    Reset,
+    Report,
+    Verify,
+    History,
+    CheckConfig,
+    UpdateHashes,
+}
+
+impl Command {
+    const ALL: [Command; 11] = [
+        Command::All,
+        Command::Watch,
+        Command::Run,
+        Command::List,
+        Command::Hint,
+        Command::Reset,
+        Command::Report,
+        Command::Verify,
+        Command::History,
+        Command::CheckConfig,
+        Command::UpdateHashes,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::All => "all",
+            Command::Watch => "watch",
+            Command::Run => "run",
+            Command::List => "list",
+            Command::Hint => "hint",
+            Command::Reset => "reset",
+            Command::Report => "report",
+            Command::Verify => "verify",
+            Command::History => "history",
+            Command::CheckConfig => "check-config",
+            Command::UpdateHashes => "update-hashes",
+        }
+    }
+
+    // 命令名之后的位置参数
+    fn arguments(&self) -> &'static str {
+        match self {
+            Command::Run | Command::Hint | Command::Reset => "<EXERCISE>...",
+            Command::Report => "[FILE]",
+            _ => "",
+        }
+    }
+
+    fn summary(&self) -> &'static str {
+        match self {
+            Command::All => "Grade every exercise (or those matching the filters) and write a report",
+            Command::Watch => "Grade exercises as you save them, stopping at the first one that does not pass",
+            Command::Run => "Grade the given exercises",
+            Command::List => "List the exercises in the config",
+            Command::Hint => "Show the hints unlocked so far for the given exercises",
+            Command::Reset => "Restore the given exercises from their templates, backing up your version",
+            Command::Report => "Summarize a saved JSON report (default report.json), optionally converting it",
+            Command::Verify => "Check the config, the toolchain and that no protected code was modified, without grading",
+            Command::History => "Show score trends, regressions and time to first pass from the grading history",
+            Command::CheckConfig => "Check the exercise config for errors",
+            Command::UpdateHashes => "Record the protected regions of the exercise templates (for maintainers)",
+        }
+    }
+}
+
+impl FromStr for Command {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(command) = Command::ALL.into_iter().find(|command| command.name() == s) {
+            return Ok(command);
+        }
+
+        // 拼写错误时给出最接近的命令
+        let closest = Command::ALL.into_iter().min_by_key(|command| edit_distance(command.name(), s));
+        match closest.filter(|command| edit_distance(command.name(), s) <= 2) {
+            Some(command) => Err(format!("Unknown command '{}', did you mean '{}'?", s, command.name())),
+            None => Err(format!("Unknown command '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Command {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// 一个命令行选项：写法、说明以及接受它的命令（为空表示所有命令）
+struct OptionSpec {
+    names: &'static [&'static str],
+    value: &'static str,
+    help: &'static str,
+    commands: &'static [Command],
+}
+
+const GRADING: &[Command] = &[Command::All, Command::Watch, Command::Run];
+const FILTERING: &[Command] = &[Command::All, Command::Watch, Command::Run, Command::List, Command::Verify];
+const REPORTING: &[Command] = &[Command::All, Command::Watch, Command::Run, Command::Report];
+
+const OPTIONS: &[OptionSpec] = &[
+    OptionSpec { names: &["--difficulty"], value: "LEVEL", help: "Only exercises of this difficulty", commands: FILTERING },
+    OptionSpec { names: &["--filter"], value: "GLOB", help: "Only exercises whose name or path matches", commands: FILTERING },
+    OptionSpec { names: &["--regex"], value: "REGEX", help: "Only exercises whose name or path matches", commands: FILTERING },
+    OptionSpec { names: &["--jobs", "-j"], value: "N", help: "Grade N exercises at a time (default 1)", commands: GRADING },
+    OptionSpec {
+        names: &["--timeout"],
+        value: "SECS",
+        help: "Time limit of each exercise (default 300)",
+        commands: GRADING,
+    },
+    OptionSpec { names: &["--no-cache"], value: "", help: "Grade again even if nothing changed", commands: GRADING },
+    OptionSpec { names: &["--no-sandbox"], value: "", help: "Run tests without resource limits", commands: GRADING },
+    OptionSpec {
+        names: &["--hidden-tests"],
+        value: "DIR",
+        help: "Replace the tests of the exercises with the ones in DIR",
+        commands: GRADING,
+    },
+    OptionSpec {
+        names: &["--format"],
+        value: "FORMAT",
+        help: "Report format: json, junit, tap or markdown (default json)",
+        commands: REPORTING,
+    },
+    OptionSpec {
+        names: &["--output", "-o"],
+        value: "PATH",
+        help: "Where to write the report (default depends on the format)",
+        commands: REPORTING,
+    },
+    OptionSpec {
+        names: &["--min-score"],
+        value: "POINTS",
+        help: "Exit with code 3 if the total score is below POINTS",
+        commands: REPORTING,
+    },
+    OptionSpec {
+        names: &["--templates"],
+        value: "DIR",
+        help: "Directory of the exercise templates (default templates)",
+        commands: &[Command::Reset, Command::UpdateHashes],
+    },
+    OptionSpec { names: &["--yes", "-y"], value: "", help: "Do not ask for confirmation", commands: &[Command::Reset] },
+    OptionSpec {
+        names: &["--status"],
+        value: "STATUS",
+        help: "Only exercises whose last result is STATUS, e.g. passed, failed, in_progress or not_run",
+        commands: &[Command::List],
+    },
+    OptionSpec {
+        names: &["--sort"],
+        value: "KEY",
+        help: "Sort by name, difficulty, type, points, result or last-run (default config order)",
+        commands: &[Command::List],
+    },
+    OptionSpec { names: &["--reverse"], value: "", help: "Reverse the order", commands: &[Command::List] },
+    OptionSpec { names: &["--json"], value: "", help: "Print the list as JSON", commands: &[Command::List] },
+    OptionSpec {
+        names: &["--config"],
+        value: "PATH",
+        help: "Exercise config (default exercise_config.json)",
+        commands: &[],
+    },
+    OptionSpec {
+        names: &["--exercises-dir"],
+        value: "DIR",
+        help: "Directory the exercise paths are relative to (default exercises)",
+        commands: &[],
+    },
+    OptionSpec { names: &["--quiet", "-q"], value: "", help: "Only show warnings and errors", commands: &[] },
+    OptionSpec {
+        names: &["--verbose", "-v"],
+        value: "",
+        help: "Also show debug output such as full compiler output; -vv shows every test",
+        commands: &[],
+    },
+    OptionSpec { names: &["--help", "-h"], value: "", help: "Show this help", commands: &[] },
+];
+
+impl OptionSpec {
+    fn applies_to(&self, command: Command) -> bool {
+        self.commands.is_empty() || self.commands.contains(&command)
+    }
+
+    fn usage(&self) -> String {
+        let names = self.names.iter().rev().copied().collect::<Vec<_>>().join(", ");
+        if self.value.is_empty() { names } else { format!("{} <{}>", names, self.value) }
+    }
+}
+
+// 命令行选项
+pub struct Options {
+    pub command: Command,
+    // 配置文件路径
+    pub config: PathBuf,
+    // 题目所在的目录，配置中的路径都相对于该目录
+    pub exercises_dir: String,
+    pub jobs: usize,
+    pub timeout: u64,
+    pub selection: Selection,
+    pub no_cache: bool,
+    // 是否在沙箱中运行测试程序
+    pub sandbox: bool,
+    // 隐藏测试目录，其中的结构与 exercises/ 相同
+    pub hidden_tests: Option<PathBuf>,
+    // reset 使用的题目模板目录
+    pub templates: PathBuf,
+    // reset 时不询问确认
+    pub yes: bool,
+    // list 只显示最近一次评测结果为这些状态的题目，为空时显示全部
+    pub statuses: Vec<Status>,
+    // list 的排序方式，为空时按配置文件中的顺序
+    pub sort: Option<SortKey>,
+    pub reverse: bool,
+    // list 以 JSON 输出
+    pub json: bool,
+    pub format: Format,
+    // 报告的输出路径，默认取决于输出格式
+    pub output: Option<String>,
+    // report 读取的 JSON 报告
+    pub report: Option<PathBuf>,
+    // 总分低于该值时以 EXIT_BELOW_THRESHOLD 退出，供 CI 判断是否通过
+    pub min_score: Option<f64>,
+    // 终端输出的详细程度：-q 只显示警告与错误，-v 显示调试信息，-vv 显示所有信息
+    pub verbosity: LevelFilter,
+}
+
+impl Options {
+    // 题目对应的隐藏测试，没有指定隐藏测试目录或其中没有该题时返回 None
+    pub fn hidden_suite(&self, exercise: &Exercise) -> Option<PathBuf> {
+        hidden::suite(self.hidden_tests.as_deref()?, exercise)
+    }
+}
+
+// 解析命令行参数失败，或者用户请求了帮助
+pub enum Usage {
+    Help(String),
+    Error(String),
+}
+
+impl From<String> for Usage {
+    fn from(message: String) -> Self {
+        Usage::Error(message)
+    }
+}
+
+// 解析命令行参数：第一个参数为子命令，其后为题目名（run、hint、reset）或报告路径（report）以及各个选项
+pub fn parse(args: &[String]) -> Result<Options, Usage> {
+    let Some(first) = args.get(1) else {
+        let names: Vec<&str> = Command::ALL.iter().map(Command::name).collect();
+        return Err(format!("Please provide a command: {}", names.join(", ")).into());
+    };
+    if first == "--help" || first == "-h" || first == "help" {
+        return match args.get(2) {
+            Some(name) => Err(Usage::Help(command_help(name.parse()?))),
+            None => Err(Usage::Help(help())),
+        };
+    }
+    let command: Command = first.parse()?;
+
+    let mut options = Options {
+        command,
+        config: PathBuf::from(CONFIG_FILE),
+        exercises_dir: DEFAULT_EXERCISES_DIR.to_string(),
+        jobs: 1,
+        timeout: DEFAULT_TIMEOUT_SECS,
+        selection: Selection::default(),
+        no_cache: false,
+        sandbox: true,
+        hidden_tests: None,
+        templates: PathBuf::from(TEMPLATES_DIR),
+        yes: false,
+        statuses: Vec::new(),
+        sort: None,
+        reverse: false,
+        json: false,
+        format: Format::Json,
+        output: None,
+        report: None,
+        min_score: None,
+        verbosity: LevelFilter::Info,
+    };
+
+    let mut rest = args[2..].iter();
+    while let Some(arg) = rest.next() {
+        if !arg.starts_with('-') {
+            match command {
+                Command::Run | Command::Hint | Command::Reset => options.selection.names.push(arg.clone()),
+                Command::Report if options.report.is_none() => options.report = Some(PathBuf::from(arg)),
+                _ => return Err(format!("Unexpected argument '{}' for '{}'", arg, command).into()),
+            }
+            continue;
+        }
+
+        // -vv 是 -v -v 的简写
+        if arg == "-vv" {
+            options.verbosity = LevelFilter::Trace;
+            continue;
+        }
+
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (arg.as_str(), None),
+        };
+        let Some(spec) = OPTIONS.iter().find(|spec| spec.names.contains(&name)) else {
+            return Err(format!("Unknown option: {}", arg).into());
+        };
+        if !spec.applies_to(command) {
+            return Err(format!("{} is not accepted by '{}'", name, command).into());
+        }
+        let value = if spec.value.is_empty() {
+            // 不带值的选项写成 --flag=value 时报错，否则 --no-cache=false 之类的写法会被当作 --no-cache
+            if value.is_some() {
+                return Err(format!("{} does not take a value", name).into());
+            }
+            None
+        } else {
+            value.or_else(|| rest.next().map(String::as_str))
+        };
+
+        match name {
+            "--help" | "-h" => return Err(Usage::Help(command_help(command))),
+            "--no-cache" => options.no_cache = true,
+            "--no-sandbox" => options.sandbox = false,
+            "--yes" | "-y" => options.yes = true,
+            "--reverse" => options.reverse = true,
+            "--json" => options.json = true,
+            "--quiet" | "-q" => options.verbosity = LevelFilter::Warn,
+            "--verbose" | "-v" => {
+                // -v -v 与 -vv 相同
+                options.verbosity =
+                    if options.verbosity >= LevelFilter::Debug { LevelFilter::Trace } else { LevelFilter::Debug };
+            }
+            "--jobs" | "-j" => options.jobs = parse_positive(name, value)? as usize,
+            "--timeout" => options.timeout = parse_positive(name, value)?,
+            "--difficulty" => options.selection.difficulties.push(required(name, value)?.parse()?),
+            "--filter" => options.selection.globs.push(required(name, value)?.to_string()),
+            "--regex" => options.selection.regexes.push(required(name, value)?.to_string()),
+            "--status" => options.statuses.push(required(name, value)?.parse()?),
+            "--sort" => options.sort = Some(required(name, value)?.parse()?),
+            "--format" => options.format = required(name, value)?.parse()?,
+            "--output" | "-o" => options.output = Some(required(name, value)?.to_string()),
+            "--templates" => options.templates = PathBuf::from(required(name, value)?),
+            "--config" => options.config = PathBuf::from(required(name, value)?),
+            "--min-score" => {
+                let value = required(name, value)?;
+                match value.parse::<f64>() {
+                    Ok(points) if points.is_finite() && points >= 0.0 => options.min_score = Some(points),
+                    _ => return Err(format!("{} expects a non-negative number", name).into()),
+                }
+            }
+            "--exercises-dir" => {
+                let dir = required(name, value)?;
+                if !PathBuf::from(dir).is_dir() {
+                    return Err(format!("Exercise directory {} does not exist", dir).into());
+                }
+                options.exercises_dir = dir.trim_end_matches('/').to_string();
+            }
+            "--hidden-tests" => {
+                let dir = PathBuf::from(required(name, value)?);
+                if !dir.is_dir() {
+                    return Err(format!("Hidden test directory {} does not exist", dir.display()).into());
+                }
+                options.hidden_tests = Some(dir);
+            }
+            _ => unreachable!("option {} has no handler", name),
+        }
+    }
+
+    if options.selection.names.is_empty() {
+        let example = match command {
+            Command::Run => "Please provide at least one exercise to run, e.g. 'run algorithm7.rs'",
+            Command::Hint => "Please provide the exercise to show hints for, e.g. 'hint algorithm14.rs'",
+            Command::Reset => "Please provide the exercise to reset, e.g. 'reset algorithm14.rs'",
+            _ => return Ok(options),
+        };
+        return Err(example.to_string().into());
+    }
+
+    Ok(options)
+}
+
+fn required<'a>(name: &str, value: Option<&'a str>) -> Result<&'a str, String> {
+    value.ok_or_else(|| format!("{} expects a value", name))
+}
+
+fn parse_positive(name: &str, value: Option<&str>) -> Result<u64, String> {
+    match value.map(str::parse::<u64>) {
+        Some(Ok(number)) if number > 0 => Ok(number),
+        _ => Err(format!("{} expects a positive integer", name)),
+    }
+}
+
+// 总的帮助信息：所有命令与通用选项
+pub fn help() -> String {
+    let mut help = String::from("Usage: cargotest <COMMAND> [OPTIONS]\n\nCommands:\n");
+    let width = Command::ALL.iter().map(|command| command_usage(*command).len()).max().unwrap_or(0);
+    for command in Command::ALL {
+        help.push_str(&format!("  {:<width$}  {}\n", command_usage(command), command.summary(), width = width));
+    }
+    help.push_str("\nOptions:\n");
+    push_options(&mut help, OPTIONS.iter().filter(|spec| spec.commands.is_empty()));
+    help.push_str("\nRun 'cargotest <COMMAND> --help' for the options of a command.\n");
+    help.push_str(&format!(
+        "\nExit codes: 0 success, 1 error, {} invalid arguments, {} score below --min-score, {} broken environment\n",
+        EXIT_USAGE, EXIT_BELOW_THRESHOLD, EXIT_ENVIRONMENT
+    ));
+    help
+}
+
+// 单个命令的帮助信息
+fn command_help(command: Command) -> String {
+    let mut help = format!("Usage: cargotest {} [OPTIONS]\n\n{}\n\nOptions:\n", command_usage(command), command.summary());
+    push_options(&mut help, OPTIONS.iter().filter(|spec| spec.applies_to(command)));
+    help
+}
+
+fn command_usage(command: Command) -> String {
+    format!("{} {}", command.name(), command.arguments()).trim_end().to_string()
+}
+
+fn push_options<'a>(help: &mut String, specs: impl Iterator<Item = &'a OptionSpec> + Clone) {
+    let width = specs.clone().map(|spec| spec.usage().len()).max().unwrap_or(0);
+    for spec in specs {
+        help.push_str(&format!("  {:<width$}  {}\n", spec.usage(), spec.help, width = width));
+    }
+}
+
+// 两个字符串之间的编辑距离，用于提示拼写错误的命令
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, y) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != *y);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_args(args: &[&str]) -> Result<Options, Usage> {
+        let args: Vec<String> = std::iter::once("cargotest").chain(args.iter().copied()).map(String::from).collect();
+        parse(&args)
+    }
+
+    fn error(args: &[&str]) -> String {
+        match parse_args(args) {
+            Err(Usage::Error(message)) => message,
+            Err(Usage::Help(_)) => panic!("{:?} printed the help", args),
+            Ok(_) => panic!("{:?} was accepted", args),
+        }
+    }
+
+    #[test]
+    fn options_take_values_inline_or_as_the_next_argument() {
+        let Ok(options) = parse_args(&["run", "a.rs", "--jobs=4", "--timeout", "10", "b.rs", "--no-cache", "-vv"])
+        else {
+            panic!("valid arguments were rejected");
+        };
+        assert_eq!(options.command, Command::Run);
+        assert_eq!(options.selection.names, ["a.rs", "b.rs"]);
+        assert_eq!((options.jobs, options.timeout), (4, 10));
+        assert!(options.no_cache && options.sandbox);
+        assert_eq!(options.verbosity, LevelFilter::Trace);
+
+        let Ok(options) = parse_args(&["all", "-v", "--verbose", "--no-sandbox", "--min-score=12.5"]) else {
+            panic!("valid arguments were rejected");
+        };
+        assert_eq!(options.verbosity, LevelFilter::Trace);
+        assert!(!options.sandbox && !options.no_cache);
+        assert_eq!(options.min_score, Some(12.5));
+    }
+
+    #[test]
+    fn invalid_arguments_are_usage_errors() {
+        assert_eq!(error(&["all", "--no-cache=false"]), "--no-cache does not take a value");
+        assert_eq!(error(&["list", "--json=1"]), "--json does not take a value");
+        assert_eq!(error(&["all", "--jobs"]), "--jobs expects a positive integer");
+        assert_eq!(error(&["all", "--jobs=0"]), "--jobs expects a positive integer");
+        assert_eq!(error(&["all", "--filter"]), "--filter expects a value");
+        assert_eq!(error(&["all", "--colour"]), "Unknown option: --colour");
+        assert_eq!(error(&["list", "--no-cache"]), "--no-cache is not accepted by 'list'");
+        assert_eq!(error(&["all", "extra"]), "Unexpected argument 'extra' for 'all'");
+        assert_eq!(error(&["run"]), "Please provide at least one exercise to run, e.g. 'run algorithm7.rs'");
+        assert_eq!(error(&["al"]), "Unknown command 'al', did you mean 'all'?");
+        assert_eq!(error(&["frobnicate"]), "Unknown command 'frobnicate'");
+        assert!(matches!(parse_args(&["all", "--help"]), Err(Usage::Help(_))));
+    }
+
+    #[test]
+    fn edit_distance_counts_insertions_deletions_and_substitutions() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("all", "all"), 0);
+        assert_eq!(edit_distance("", "run"), 3);
+        assert_eq!(edit_distance("run", ""), 3);
+        assert_eq!(edit_distance("hint", "hnt"), 1);
+        assert_eq!(edit_distance("list", "lost"), 1);
+        assert_eq!(edit_distance("reset", "rest"), 1);
+        assert_eq!(edit_distance("verify", "verfy"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("watch", "wacth"), 2);
+    }
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::libtest::{self, TestStatus};
+use crate::sandbox::Violation;
+
+// 报告中保留的 stderr 最大字节数
+const MAX_CAPTURE_BYTES: usize = 4096;
+
+// 评测阶段
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    Compile,
+    Build,
+    Test,
+    // 运行程序的 main 函数（output_compare、benchmark）
+    Run,
+    Clippy,
+    // 评测前检查受保护区域（测试模块、函数签名等）是否被修改
+    Integrity,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Compile => "compile",
+            Stage::Build => "build",
+            Stage::Test => "test",
+            Stage::Run => "run",
+            Stage::Clippy => "clippy",
+            Stage::Integrity => "integrity",
+        }
+    }
+}
+
+// 未通过的习题在报告中附带的诊断信息
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Diagnostics {
+    // 失败（或超时）时所处的阶段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stage: Option<Stage>,
+    // 子进程的退出码，超时或被信号杀死时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_status: Option<i32>,
+    // 终止子进程的信号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signal: Option<i32>,
+    // 子进程使用的 CPU 时间（毫秒），用于判断被 SIGKILL 杀死的测试程序是否超出了 CPU 时间限制
+    #[serde(skip)]
+    pub cpu_time_ms: u32,
+    // 超出的资源限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub violation: Option<Violation>,
+    // 截断后的 stderr
+    #[serde(default)]
+    pub stderr: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failing_tests: Vec<String>,
+    // 测试程序宣布要运行的测试总数，测试程序崩溃时可能多于有结果的测试
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_tests: Option<u32>,
+    // 命令完整的 stdout 与 stderr，只写入日志文件，不进入报告
+    #[serde(skip)]
+    pub full_output: String,
+}
+
+impl Diagnostics {
+    pub fn new(stage: Stage, exit_status: Option<i32>, stdout: &[u8], stderr: &[u8]) -> Self {
+        Diagnostics {
+            stage: Some(stage),
+            exit_status,
+            signal: None,
+            cpu_time_ms: 0,
+            violation: None,
+            stderr: truncate_capture(stderr),
+            failing_tests: libtest::parse_output(stdout)
+                .into_iter()
+                .filter(|test| test.status == TestStatus::Failed)
+                .map(|test| test.name)
+                .collect(),
+            expected_tests: libtest::expected_count(stdout),
+            full_output: format!(
+                "--- stdout ---\n{}\n--- stderr ---\n{}",
+                String::from_utf8_lossy(stdout).trim_end(),
+                String::from_utf8_lossy(stderr).trim_end()
+            ),
+        }
+    }
+}
+
+// 截取输出的开头部分，编译错误通常最先出现的几条最有用
+pub fn truncate_capture(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    if text.len() <= MAX_CAPTURE_BYTES {
+        return text.into_owned();
+    }
+
+    let mut end = MAX_CAPTURE_BYTES;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}\n... ({} bytes truncated)", &text[..end], text.len() - end)
+}
@@ -0,0 +1,166 @@
+use std::fmt::Write;
+
+// 每个差异块前后保留的上下文行数
+const CONTEXT: usize = 3;
+
+// 两段文本的行数乘积超过这个值时不再计算最长公共子序列，直接视为整段被替换
+const MAX_CELLS: usize = 4_000_000;
+
+enum Edit<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+// 按行比较两段文本并生成 unified diff，same 决定两行是否视为相同（例如允许浮点误差）
+pub fn unified(old: &[&str], new: &[&str], same: impl Fn(&str, &str) -> bool, old_name: &str, new_name: &str) -> String {
+    let edits = edits(old, new, same);
+    let mut diff = String::new();
+    writeln!(diff, "--- {}\n+++ {}", old_name, new_name).ok();
+
+    // 每个编辑之前已经经过的旧、新行数
+    let mut positions = Vec::with_capacity(edits.len());
+    let (mut old_line, mut new_line) = (0, 0);
+    for edit in &edits {
+        positions.push((old_line, new_line));
+        match edit {
+            Edit::Same(_) => (old_line, new_line) = (old_line + 1, new_line + 1),
+            Edit::Removed(_) => old_line += 1,
+            Edit::Added(_) => new_line += 1,
+        }
+    }
+
+    let changed: Vec<usize> = edits
+        .iter()
+        .enumerate()
+        .filter(|(_, edit)| !matches!(edit, Edit::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+
+    let mut next = 0;
+    while next < changed.len() {
+        let start = changed[next].saturating_sub(CONTEXT);
+        let mut last = changed[next];
+        // 上下文会重叠的改动合并到同一个块中
+        while next + 1 < changed.len() && changed[next + 1] - last <= 2 * CONTEXT {
+            next += 1;
+            last = changed[next];
+        }
+        next += 1;
+
+        let hunk = &edits[start..(last + CONTEXT + 1).min(edits.len())];
+        let (old_start, new_start) = positions[start];
+        let old_count = hunk.iter().filter(|edit| !matches!(edit, Edit::Added(_))).count();
+        let new_count = hunk.iter().filter(|edit| !matches!(edit, Edit::Removed(_))).count();
+        writeln!(diff, "@@ -{} +{} @@", range(old_start, old_count), range(new_start, new_count)).ok();
+
+        for edit in hunk {
+            match edit {
+                Edit::Same(line) => writeln!(diff, " {}", line),
+                Edit::Removed(line) => writeln!(diff, "-{}", line),
+                Edit::Added(line) => writeln!(diff, "+{}", line),
+            }
+            .ok();
+        }
+    }
+
+    diff
+}
+
+// 行号从 1 开始；空范围使用前一行的行号
+fn range(start: usize, count: usize) -> String {
+    match count {
+        0 => format!("{},0", start),
+        1 => format!("{}", start + 1),
+        _ => format!("{},{}", start + 1, count),
+    }
+}
+
+// 基于最长公共子序列的逐行编辑序列
+fn edits<'a>(old: &[&'a str], new: &[&'a str], same: impl Fn(&str, &str) -> bool) -> Vec<Edit<'a>> {
+    let (n, m) = (old.len(), new.len());
+    if n.saturating_mul(m) > MAX_CELLS {
+        let removed = old.iter().map(|line| Edit::Removed(line));
+        return removed.chain(new.iter().map(|line| Edit::Added(line))).collect();
+    }
+
+    // lcs[i][j] 为 old[i..] 与 new[j..] 的最长公共子序列长度
+    let mut lcs = vec![vec![0u32; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if same(old[i], new[j]) {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && same(old[i], new[j]) {
+            edits.push(Edit::Same(new[j]));
+            (i, j) = (i + 1, j + 1);
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            edits.push(Edit::Removed(old[i]));
+            i += 1;
+        } else {
+            edits.push(Edit::Added(new[j]));
+            j += 1;
+        }
+    }
+    edits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(old: &[&str], new: &[&str]) -> String {
+        unified(old, new, |old, new| old == new, "expected", "actual")
+    }
+
+    #[test]
+    fn identical_texts_have_no_hunks() {
+        assert_eq!(diff(&["a", "b"], &["a", "b"]), "--- expected\n+++ actual\n");
+        assert_eq!(diff(&[], &[]), "--- expected\n+++ actual\n");
+    }
+
+    #[test]
+    fn empty_expected_output_is_an_empty_range() {
+        assert_eq!(diff(&[], &["x", "y"]), "--- expected\n+++ actual\n@@ -0,0 +1,2 @@\n+x\n+y\n");
+        assert_eq!(diff(&["x"], &[]), "--- expected\n+++ actual\n@@ -1 +0,0 @@\n-x\n");
+    }
+
+    #[test]
+    fn missing_final_newline_shows_as_an_empty_line() {
+        // 按 '\n' 切分后，末尾的换行表现为最后一个空行
+        let expected: Vec<&str> = "a\nb\n".split('\n').collect();
+        let actual: Vec<&str> = "a\nb".split('\n').collect();
+        assert_eq!(diff(&expected, &actual), "--- expected\n+++ actual\n@@ -1,3 +1,2 @@\n a\n b\n-\n");
+    }
+
+    #[test]
+    fn distant_changes_are_separate_hunks() {
+        let old: Vec<String> = (1..=20).map(|line| line.to_string()).collect();
+        let mut new = old.clone();
+        new[1] = "two".to_string();
+        new[18] = "nineteen".to_string();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let new: Vec<&str> = new.iter().map(String::as_str).collect();
+        assert_eq!(
+            diff(&old, &new),
+            "--- expected\n+++ actual\n\
+             @@ -1,5 +1,5 @@\n 1\n-2\n+two\n 3\n 4\n 5\n\
+             @@ -16,5 +16,5 @@\n 16\n 17\n 18\n-19\n+nineteen\n 20\n"
+        );
+    }
+
+    #[test]
+    fn same_decides_which_lines_match() {
+        // 视为相同的行按新文本显示
+        let diff = unified(&["A", "b"], &["a", "c"], |old, new| old.eq_ignore_ascii_case(new), "old", "new");
+        assert_eq!(diff, "--- old\n+++ new\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n");
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Instant;
+
+use super::{Context, Evaluator, Program, check_program, print_result};
+use crate::diagnostics::{Diagnostics, Stage};
+use crate::libtest::{TestCase, TestStatus};
+use crate::{ExerciseOutput, Outcome, StageFailure};
+
+// 使用优化配置编译程序并多次运行 main 函数，最快一次的耗时不能超过 max_ms，
+// 例如 `{ "type": "benchmark", "path": "hard/solutiont3", "max_ms": 500, "runs": 5 }`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Benchmark {
+    // 允许的最长运行时间（毫秒）
+    pub max_ms: u64,
+    // 运行次数，取最快的一次
+    #[serde(default = "default_runs")]
+    pub runs: u32,
+}
+
+fn default_runs() -> u32 {
+    3
+}
+
+impl Evaluator for Benchmark {
+    fn check(&self, path: &Path) -> Result<(), String> {
+        check_program(path)?;
+        if self.max_ms == 0 {
+            return Err("max_ms must be positive".to_string());
+        }
+        if self.runs == 0 {
+            return Err("runs must be positive".to_string());
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let result = self.measure(context, output);
+        print_result(context.source, "", &result, output);
+        result
+    }
+}
+
+impl Benchmark {
+    fn measure(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let program = Program::build(context.path, context.build_dir, true, context.deadline)?;
+
+        let mut best_ms = f64::MAX;
+        for _ in 0..self.runs {
+            let start = Instant::now();
+            program.run(context, &[], None)?;
+            best_ms = best_ms.min(start.elapsed().as_secs_f64() * 1000.0);
+        }
+        // 保留三位小数
+        let best_ms = (best_ms * 1000.0).round() / 1000.0;
+
+        let summary = format!("best of {} run(s): {}ms, limit {}ms", self.runs, best_ms, self.max_ms);
+        output.info(format!("Benchmark: {}", summary));
+
+        if best_ms <= self.max_ms as f64 {
+            return Ok(vec![TestCase {
+                name: "benchmark".to_string(),
+                status: TestStatus::Passed,
+                message: None,
+                duration_ms: Some(best_ms),
+            }]);
+        }
+
+        Err(StageFailure {
+            outcome: Outcome::Failed,
+            diagnostics: Box::new(Diagnostics {
+                stage: Some(Stage::Run),
+                stderr: format!("too slow: {}", summary),
+                failing_tests: vec!["benchmark".to_string()],
+                ..Diagnostics::default()
+            }),
+            tests: vec![TestCase {
+                name: "benchmark".to_string(),
+                status: TestStatus::Failed,
+                message: Some(format!("too slow: {}", summary)),
+                duration_ms: Some(best_ms),
+            }],
+        })
+    }
+}
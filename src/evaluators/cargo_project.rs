@@ -0,0 +1,253 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use super::doc_test::run_doc_tests;
+use super::{
+    Context, Evaluator, OutputCompare, cargo, check_cargo_project, command_result, print_result, run_command, run_stage,
+    run_tests, sandboxed,
+};
+use crate::diagnostics::{Stage, truncate_capture};
+use crate::libtest::{self, TestCase, TestStatus};
+use crate::lints::{self, LintLevel};
+use crate::process::{CommandOutcome, run_with_deadline};
+use crate::{ExerciseOutput, Outcome, StageFailure};
+
+// Cargo 项目：依次执行 build、test、clippy，遇到第一个未通过的阶段即停止。
+// 设置了 output 时最后还要运行程序并比较标准输出，例如
+// `{ "type": "cargo_project", "path": "hard/solutiont4", "output": { "expected": "hard/solutiont4.out" } }`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CargoProject {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<OutputCompare>,
+}
+
+impl Evaluator for CargoProject {
+    fn check(&self, path: &Path) -> Result<(), String> {
+        check_cargo_project(path)?;
+        match &self.output {
+            Some(compare) => compare.check_files().map_err(|message| format!("output: {}", message)),
+            None => Ok(()),
+        }
+    }
+
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let result = build_test_and_lint(context, &[]).and_then(|mut tests| match &self.output {
+            Some(compare) => match compare.compare(context, output) {
+                Ok(cases) => {
+                    tests.extend(cases);
+                    Ok(tests)
+                }
+                Err(failure) => Err(StageFailure { tests: [tests, failure.tests].concat(), ..failure }),
+            },
+            None => Ok(tests),
+        });
+        print_result(context.source, "", &result, output);
+        result
+    }
+
+    fn inputs(&self) -> Vec<PathBuf> {
+        self.output.as_ref().map(|compare| compare.inputs()).unwrap_or_default()
+    }
+
+    fn uses_clippy(&self) -> bool {
+        true
+    }
+}
+
+// 对项目（或工作区中由 package_args 选中的成员）执行 build、test、clippy
+pub(super) fn build_test_and_lint(context: &Context, package_args: &[&str]) -> Result<Vec<TestCase>, StageFailure> {
+    let (path, build_dir, deadline) = (context.path, context.build_dir, context.deadline);
+
+    run_stage(Stage::Build, cargo(path, build_dir).arg("build").args(package_args), deadline)
+        .and_then(|_| test(context, package_args))
+        .and_then(|tests| {
+            lint(context, package_args).map(|_| tests.clone()).map_err(|failure| StageFailure { tests, ..failure })
+        })
+}
+
+// 编译好的测试程序
+struct TestBinary {
+    executable: PathBuf,
+    // 与 cargo test 一样在所属包的目录中运行
+    package_dir: PathBuf,
+    order: (u8, String),
+}
+
+// cargo test 依次运行库、二进制程序与集成测试的测试程序，同类的按目标名排序
+fn target_order(artifact: &serde_json::Value) -> (u8, String) {
+    let target = &artifact["target"];
+    let rank = match target["kind"][0].as_str() {
+        Some("lib" | "rlib" | "proc-macro") => 0,
+        Some("bin") => 1,
+        _ => 2,
+    };
+    (rank, target["name"].as_str().unwrap_or_default().to_string())
+}
+
+// 先用 cargo test --no-run 编译测试程序，再逐个运行，相当于 cargo test --no-fail-fast。
+// 请求 JSON 输出需要的 RUSTC_BOOTSTRAP 只设置给测试程序，不影响题目代码的编译
+fn test(context: &Context, package_args: &[&str]) -> Result<Vec<TestCase>, StageFailure> {
+    let mut command = cargo(context.path, context.build_dir);
+    command.args(["test", "--no-run", "--message-format=json-render-diagnostics"]).args(package_args);
+    let out = run_command(Stage::Test, &mut command, context.deadline)?;
+
+    // cargo 以 JSON 消息输出构建产物：测试程序的 profile.test 为真；
+    // 被测的包中有库时（产物中同一个包的库），还要运行它的文档测试
+    let artifacts: Vec<serde_json::Value> = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+        .filter(|message| message["reason"] == "compiler-artifact")
+        .collect();
+    let package_dir = |artifact: &serde_json::Value| {
+        artifact["manifest_path"].as_str().and_then(|manifest| Path::new(manifest).parent()).map(Path::to_path_buf)
+    };
+    let mut binaries: Vec<TestBinary> = artifacts
+        .iter()
+        .filter(|artifact| artifact["profile"]["test"] == true)
+        .filter_map(|artifact| {
+            let executable = PathBuf::from(artifact["executable"].as_str()?);
+            Some(TestBinary { executable, package_dir: package_dir(artifact)?, order: target_order(artifact) })
+        })
+        .collect();
+    // 产物的输出顺序取决于编译完成的先后，按 cargo test 的顺序排序使测试结果的顺序固定
+    binaries.sort_by(|a, b| a.order.cmp(&b.order));
+    let doc_tests = artifacts.iter().any(|artifact| {
+        artifact["profile"]["test"] == false
+            && artifact["target"]["doctest"] == true
+            && package_dir(artifact).is_some_and(|dir| binaries.iter().any(|binary| binary.package_dir == dir))
+    });
+
+    let mut results = Vec::new();
+    for binary in &binaries {
+        let result = sandboxed(Stage::Test, context.limits, |sandbox| {
+            run_tests(
+                |json| {
+                    let mut command = Command::new(&binary.executable);
+                    match sandbox {
+                        Some(sandbox) => sandbox.apply_in(&mut command, &binary.package_dir),
+                        None => {
+                            command.current_dir(&binary.package_dir);
+                        }
+                    }
+                    if json {
+                        libtest::request_json(&mut command);
+                    }
+                    command
+                },
+                context.deadline,
+            )
+        });
+        // 超时或无法运行时不再运行其余的测试程序
+        let stop = matches!(&result, Err(failure) if failure.outcome != Outcome::Failed);
+        results.push(result);
+        if stop {
+            return merge_results(results);
+        }
+    }
+    if doc_tests {
+        results.push(run_doc_tests(context, package_args));
+    }
+    merge_results(results)
+}
+
+// 合并各个测试程序的结果。有测试程序未通过时返回第一个未通过的结论，
+// 其中的测试结果、未通过的测试与测试总数汇总所有测试程序
+fn merge_results(results: Vec<Result<Vec<TestCase>, StageFailure>>) -> Result<Vec<TestCase>, StageFailure> {
+    let mut tests = Vec::new();
+    let mut expected = 0;
+    let mut first_failure = None;
+    for result in results {
+        match result {
+            Ok(cases) => {
+                expected += cases.len();
+                tests.extend(cases);
+            }
+            Err(StageFailure { outcome, diagnostics, tests: cases }) => {
+                expected += cases.len().max(diagnostics.expected_tests.unwrap_or(0) as usize);
+                tests.extend(cases);
+                first_failure.get_or_insert((outcome, diagnostics));
+            }
+        }
+    }
+
+    match first_failure {
+        None => Ok(tests),
+        Some((outcome, mut diagnostics)) => {
+            diagnostics.failing_tests = tests
+                .iter()
+                .filter(|test| test.status == TestStatus::Failed)
+                .map(|test| test.name.clone())
+                .collect();
+            diagnostics.expected_tests = u32::try_from(expected).ok();
+            Err(StageFailure { outcome, diagnostics, tests })
+        }
+    }
+}
+
+// 按题目的 clippy 规则运行 clippy，发现的问题记录在 context.lints 中。
+// 规则中设置了 deduct 时，lint 导致的失败改为扣分（由计分时处理）
+fn lint(context: &Context, package_args: &[&str]) -> Result<(), StageFailure> {
+    let mut command = cargo(context.path, context.build_dir);
+    command
+        .args(["clippy", "--message-format=json"])
+        .args(package_args)
+        .arg("--")
+        .args(context.clippy.args());
+    let result = run_with_deadline(&mut command, context.deadline);
+
+    let findings = match &result {
+        Ok(CommandOutcome::Finished(out, _)) => lints::parse(&out.stdout),
+        _ => Vec::new(),
+    };
+    let deducted = context.clippy.deducts(&findings);
+    // 诊断信息以 JSON 输出在 stdout 中，报告中的 stderr 改用其中渲染好的错误
+    let rendered: String = findings
+        .iter()
+        .filter(|finding| finding.level == LintLevel::Error)
+        .map(|finding| finding.rendered.as_str())
+        .collect();
+    context.lints.borrow_mut().extend(findings);
+
+    match command_result(Stage::Clippy, &command, result) {
+        Ok(_) => Ok(()),
+        Err(failure) if failure.outcome == Outcome::Failed && deducted => Ok(()),
+        Err(mut failure) => {
+            if failure.outcome == Outcome::Failed && !rendered.is_empty() {
+                failure.diagnostics.stderr = truncate_capture(rendered.as_bytes());
+            }
+            Err(failure)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Diagnostics;
+
+    fn case(name: &str, status: TestStatus) -> TestCase {
+        TestCase { name: name.to_string(), status, message: None, duration_ms: None }
+    }
+
+    #[test]
+    fn merged_results_cover_every_test_binary() {
+        let passed = merge_results(vec![Ok(vec![case("a", TestStatus::Passed)]), Ok(Vec::new())]);
+        assert_eq!(passed.map(|tests| tests.len()).ok(), Some(1));
+
+        // 第二个测试程序在运行 c 时崩溃，宣布的 3 个测试中只有 c 有结果
+        let crashed = StageFailure {
+            outcome: Outcome::Failed,
+            diagnostics: Box::new(Diagnostics { signal: Some(6), expected_tests: Some(3), ..Diagnostics::default() }),
+            tests: vec![case("c", TestStatus::Failed)],
+        };
+        let results = vec![Ok(vec![case("a", TestStatus::Passed), case("b", TestStatus::Failed)]), Err(crashed)];
+        let Err(failure) = merge_results(results) else { panic!("a failed test binary must fail the stage") };
+        assert_eq!(failure.outcome, Outcome::Failed);
+        assert_eq!(failure.diagnostics.signal, Some(6));
+        assert_eq!(failure.diagnostics.expected_tests, Some(5));
+        assert_eq!(failure.diagnostics.failing_tests, ["b", "c"]);
+        assert_eq!(failure.tests.len(), 3);
+    }
+}
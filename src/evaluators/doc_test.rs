@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::{Context, Evaluator, cargo, check_cargo_project, print_result, run_tests, sandboxed};
+use crate::diagnostics::Stage;
+use crate::libtest::{self, TestCase};
+use crate::{ExerciseOutput, StageFailure};
+
+// 库项目中的文档测试：使用 cargo test --doc 评测
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DocTest {}
+
+impl Evaluator for DocTest {
+    fn check(&self, path: &Path) -> Result<(), String> {
+        check_cargo_project(path)?;
+
+        // 只有库才有文档测试
+        let manifest = fs::read_to_string(path.join("Cargo.toml")).map_err(|e| e.to_string())?;
+        if !path.join("src/lib.rs").is_file() && !manifest.lines().any(|line| line.trim() == "[lib]") {
+            return Err(format!("{} has no library target", path.display()));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let result = run_doc_tests(context, &[]);
+        print_result(context.source, "", &result, output);
+        result
+    }
+}
+
+// 运行项目（或工作区中由 package_args 选中的成员）的文档测试
+pub(super) fn run_doc_tests(context: &Context, package_args: &[&str]) -> Result<Vec<TestCase>, StageFailure> {
+    sandboxed(Stage::Test, context.limits, |sandbox| {
+        run_tests(
+            |json| {
+                let mut command = cargo(context.path, context.build_dir);
+                command.args(["test", "--doc", "--no-fail-fast"]).args(package_args);
+                if let Some(sandbox) = sandbox {
+                    sandbox.limit(&mut command);
+                }
+                if json {
+                    libtest::request_doc_json(&mut command);
+                }
+                command
+            },
+            context.deadline,
+        )
+    })
+}
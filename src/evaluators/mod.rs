@@ -0,0 +1,323 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{Duration, Instant};
+
+use crate::diagnostics::{Diagnostics, Stage};
+use crate::libtest::{self, TestCase};
+use crate::lints::{ClippyPolicy, Finding};
+use crate::process::{CommandOutcome, exit_signal, run_with_deadline, run_with_input};
+use crate::sandbox::{self, Limits, Sandbox};
+use crate::toolchain;
+use crate::{ExerciseOutput, Outcome, StageFailure};
+
+mod benchmark;
+mod cargo_project;
+mod doc_test;
+mod output_compare;
+mod single_file;
+mod workspace_member;
+
+pub use benchmark::Benchmark;
+pub use cargo_project::CargoProject;
+pub use doc_test::DocTest;
+pub use output_compare::OutputCompare;
+pub use single_file::SingleFile;
+pub use workspace_member::WorkspaceMember;
+
+// 一种题目类型的评测方式。新增题目类型时实现这个 trait，并在 ExerciseKind 中添加对应的变体
+pub trait Evaluator {
+    // 检查该类型特有的配置，path 为题目源文件（或目录）的路径
+    fn check(&self, path: &Path) -> Result<(), String>;
+
+    // 评测一道题，返回各个测试的结果
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure>;
+
+    // 题目目录以外、会影响评测结果的文件（例如期望输出），用于计算缓存键
+    fn inputs(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    // 评测是否需要运行 clippy，评测前据此检查 clippy 是否已安装
+    fn uses_clippy(&self) -> bool {
+        false
+    }
+}
+
+// 评测一道题时需要的信息
+pub struct Context<'a> {
+    // 题目源文件（或 Cargo 项目目录）
+    pub path: &'a Path,
+    // 题目在 exercises/ 中的路径，用于输出；使用隐藏测试时 path 指向临时副本
+    pub source: &'a Path,
+    // 本题的临时构建目录
+    pub build_dir: &'a Path,
+    // 整道题共用的截止时间
+    pub deadline: Instant,
+    // 沙箱的资源限制，使用 --no-sandbox 时为空
+    pub limits: Option<&'a Limits>,
+    // 题目的 clippy 规则
+    pub clippy: &'a ClippyPolicy,
+    // 运行 clippy 时发现的问题，无论是否通过都会写入报告
+    pub lints: &'a RefCell<Vec<Finding>>,
+}
+
+// 题目类型及其特有的配置，对应 exercise_config.json 中的 `type` 字段与同一对象中的其余字段，例如
+// `{ "type": "cargo_workspace_member", "package": "solver", ... }`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExerciseKind {
+    SingleFile(SingleFile),
+    CargoProject(CargoProject),
+    CargoWorkspaceMember(WorkspaceMember),
+    DocTest(DocTest),
+    OutputCompare(OutputCompare),
+    Benchmark(Benchmark),
+}
+
+impl ExerciseKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExerciseKind::SingleFile(_) => "single_file",
+            ExerciseKind::CargoProject(_) => "cargo_project",
+            ExerciseKind::CargoWorkspaceMember(_) => "cargo_workspace_member",
+            ExerciseKind::DocTest(_) => "doc_test",
+            ExerciseKind::OutputCompare(_) => "output_compare",
+            ExerciseKind::Benchmark(_) => "benchmark",
+        }
+    }
+
+    pub fn evaluator(&self) -> &dyn Evaluator {
+        match self {
+            ExerciseKind::SingleFile(evaluator) => evaluator,
+            ExerciseKind::CargoProject(evaluator) => evaluator,
+            ExerciseKind::CargoWorkspaceMember(evaluator) => evaluator,
+            ExerciseKind::DocTest(evaluator) => evaluator,
+            ExerciseKind::OutputCompare(evaluator) => evaluator,
+            ExerciseKind::Benchmark(evaluator) => evaluator,
+        }
+    }
+}
+
+// 各题目类型对路径的要求
+fn check_rust_file(path: &Path) -> Result<(), String> {
+    if !path.is_file() {
+        return Err(format!("file {} does not exist", path.display()));
+    }
+    if path.extension().is_none_or(|extension| extension != "rs") {
+        return Err(format!("{} is not a .rs file", path.display()));
+    }
+    Ok(())
+}
+
+fn check_cargo_project(path: &Path) -> Result<(), String> {
+    if !path.join("Cargo.toml").is_file() {
+        return Err(format!("{} has no Cargo.toml", path.display()));
+    }
+    Ok(())
+}
+
+// 单文件程序或 Cargo 项目
+fn check_program(path: &Path) -> Result<(), String> {
+    if path.is_dir() { check_cargo_project(path) } else { check_rust_file(path) }
+}
+
+// 输出题目的评测结论，label 加在 PASSED/FAILED 之前（单文件题目为 "TEST "，其余为空）
+fn print_result(path: &Path, label: &str, result: &Result<Vec<TestCase>, StageFailure>, output: &mut ExerciseOutput) {
+    match result {
+        Ok(_) => output.info(format!("{}: {}PASSED", path.display(), label).green().to_string()),
+        Err(failure) if failure.outcome == Outcome::Failed => match failure.diagnostics.signal {
+            // 被信号终止但无法判断超出了哪一项资源限制
+            Some(signal) => output.info(
+                format!("{}: {}FAILED (killed by signal {})", path.display(), label, signal).red().to_string(),
+            ),
+            None => output.info(format!("{}: {}FAILED", path.display(), label).red().to_string()),
+        },
+        Err(failure) if failure.outcome == Outcome::SandboxViolation => {
+            let violation = failure.diagnostics.violation.map_or("", |violation| violation.description());
+            output.info(format!("{}: SANDBOX VIOLATION ({})", path.display(), violation).red().to_string())
+        }
+        Err(_) => {}
+    }
+}
+
+// 在项目目录中运行的 Cargo 命令，构建产物写入临时构建目录而不是项目的 target 目录
+fn cargo(proj_path: &Path, build_dir: &Path) -> Command {
+    let mut command = Command::new("cargo");
+    command.current_dir(proj_path).env("CARGO_TARGET_DIR", build_dir.join("target"));
+    command
+}
+
+// 使用 rustc 把单文件程序编译到构建目录中，返回可执行文件的绝对路径
+fn compile(file_path: &Path, build_dir: &Path, args: &[&str], deadline: Instant) -> Result<PathBuf, StageFailure> {
+    // 文件名为源文件名（不带扩展名）
+    let binary = build_dir.join(file_path.file_stem().unwrap_or_default());
+
+    run_command(
+        Stage::Compile,
+        Command::new("rustc").args(args).arg(file_path).arg("-o").arg(&binary),
+        deadline,
+    )?;
+
+    Ok(std::path::absolute(&binary).unwrap_or(binary))
+}
+
+// 在沙箱中执行 run；创建沙箱失败或超出资源限制时返回对应的结论
+fn sandboxed<T>(
+    stage: Stage,
+    limits: Option<&Limits>,
+    run: impl FnOnce(Option<&Sandbox>) -> Result<T, StageFailure>,
+) -> Result<T, StageFailure> {
+    let Some(limits) = limits else {
+        return run(None);
+    };
+
+    match Sandbox::new(limits.clone()) {
+        Ok(sandbox) => run(Some(&sandbox)).map_err(|failure| check_violation(failure, limits)),
+        Err(e) => Err(StageFailure {
+            outcome: Outcome::Failed,
+            diagnostics: Box::new(Diagnostics {
+                stage: Some(stage),
+                stderr: format!("Failed to create sandbox: {}", e),
+                ..Diagnostics::default()
+            }),
+            tests: Vec::new(),
+        }),
+    }
+}
+
+// 程序因超出资源限制而失败时，改为记作 sandbox_violation
+fn check_violation(mut failure: StageFailure, limits: &Limits) -> StageFailure {
+    if failure.outcome == Outcome::Failed {
+        let diagnostics = &failure.diagnostics;
+        let cpu_time = Duration::from_millis(diagnostics.cpu_time_ms.into());
+        let violation = sandbox::violation(limits, diagnostics.signal, cpu_time, &diagnostics.stderr, &failure.tests);
+        if let Some(violation) = violation {
+            failure.outcome = Outcome::SandboxViolation;
+            failure.diagnostics.violation = Some(violation);
+        }
+    }
+    failure
+}
+
+// 编译好的题目程序，output_compare 与 benchmark 运行它的 main 函数
+struct Program {
+    executable: PathBuf,
+    // Cargo 项目的程序与 cargo run 一样在项目目录中运行，以便读取项目中的数据文件；
+    // 单文件程序在沙箱的临时目录中运行
+    project_dir: Option<PathBuf>,
+}
+
+impl Program {
+    // 编译单文件程序或 Cargo 项目中的可执行文件，optimized 时使用 release 配置
+    fn build(path: &Path, build_dir: &Path, optimized: bool, deadline: Instant) -> Result<Self, StageFailure> {
+        if !path.is_dir() {
+            let args: &[&str] = if optimized { &["-O"] } else { &[] };
+            let executable = compile(path, build_dir, args, deadline)?;
+            return Ok(Program { executable, project_dir: None });
+        }
+
+        let mut command = cargo(path, build_dir);
+        command.args(["build", "--message-format=json-render-diagnostics"]);
+        if optimized {
+            command.arg("--release");
+        }
+        let out = run_command(Stage::Build, &mut command, deadline)?;
+
+        // cargo 以 JSON 消息输出构建产物，取最后一个可执行文件
+        let executable = String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .filter(|message| message["reason"] == "compiler-artifact")
+            .filter_map(|message| message["executable"].as_str().map(PathBuf::from))
+            .next_back();
+
+        match executable {
+            Some(executable) => Ok(Program { executable, project_dir: Some(path.to_path_buf()) }),
+            None => Err(StageFailure {
+                outcome: Outcome::Failed,
+                diagnostics: Box::new(Diagnostics {
+                    stage: Some(Stage::Build),
+                    stderr: format!("{} has no binary target", path.display()),
+                    ..Diagnostics::default()
+                }),
+                tests: Vec::new(),
+            }),
+        }
+    }
+
+    // 运行程序并返回其输出，stdin 不为空时写入程序的标准输入
+    fn run(&self, context: &Context, args: &[String], stdin: Option<&[u8]>) -> Result<Output, StageFailure> {
+        sandboxed(Stage::Run, context.limits, |sandbox| {
+            let mut command = Command::new(&self.executable);
+            command.args(args);
+            match (sandbox, &self.project_dir) {
+                (Some(sandbox), Some(project_dir)) => sandbox.apply_in(&mut command, project_dir),
+                (Some(sandbox), None) => sandbox.apply(&mut command),
+                (None, Some(project_dir)) => {
+                    command.current_dir(project_dir);
+                }
+                (None, None) => {}
+            }
+            let result = run_with_input(&mut command, stdin, context.deadline);
+            command_result(Stage::Run, &command, result)
+        })
+    }
+}
+
+// 运行测试阶段，优先让 libtest 输出 JSON 事件，测试程序不支持时退回到普通文本输出
+fn run_tests(make_command: impl Fn(bool) -> Command, deadline: Instant) -> Result<Vec<TestCase>, StageFailure> {
+    let mut command = make_command(true);
+    let result = run_with_deadline(&mut command, deadline);
+
+    if let Ok(CommandOutcome::Finished(out, _)) = &result {
+        if !out.status.success() && libtest::json_unsupported(&out.stderr) {
+            return run_stage(Stage::Test, &mut make_command(false), deadline);
+        }
+    }
+
+    command_result(Stage::Test, &command, result).map(|out| libtest::parse_output(&out.stdout))
+}
+
+// 执行评测的一个阶段，返回输出中解析到的测试结果；未通过时返回评测结论与诊断信息
+fn run_stage(stage: Stage, command: &mut Command, deadline: Instant) -> Result<Vec<TestCase>, StageFailure> {
+    run_command(stage, command, deadline).map(|out| libtest::parse_output(&out.stdout))
+}
+
+// 执行评测的一个阶段，返回命令的输出
+fn run_command(stage: Stage, command: &mut Command, deadline: Instant) -> Result<Output, StageFailure> {
+    let result = run_with_deadline(command, deadline);
+    command_result(stage, command, result)
+}
+
+fn command_result(stage: Stage, command: &Command, result: io::Result<CommandOutcome>) -> Result<Output, StageFailure> {
+    let (outcome, diagnostics, stdout) = match result {
+        Ok(CommandOutcome::Finished(out, _)) if out.status.success() => return Ok(out),
+        Ok(CommandOutcome::Finished(out, cpu_time)) => (
+            // 缺少工具链组件（例如 clippy）是评测环境的问题，不算题目未通过
+            if toolchain::missing_component(&out.stderr) { Outcome::EnvironmentError } else { Outcome::Failed },
+            Diagnostics {
+                signal: exit_signal(&out.status),
+                cpu_time_ms: u32::try_from(cpu_time.as_millis()).unwrap_or(u32::MAX),
+                ..Diagnostics::new(stage, out.status.code(), &out.stdout, &out.stderr)
+            },
+            out.stdout,
+        ),
+        Ok(CommandOutcome::TimedOut { stdout, stderr }) => (
+            Outcome::TimedOut,
+            Diagnostics::new(stage, None, &stdout, &stderr),
+            stdout,
+        ),
+        // 无法启动命令（例如找不到 rustc 或 cargo）同样是评测环境的问题
+        Err(e) => (
+            Outcome::EnvironmentError,
+            Diagnostics::new(stage, None, &[], format!("Error executing {:?}: {}", command.get_program(), e).as_bytes()),
+            Vec::new(),
+        ),
+    };
+
+    Err(StageFailure { outcome, diagnostics: Box::new(diagnostics), tests: libtest::parse_output(&stdout) })
+}
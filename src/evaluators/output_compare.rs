@@ -0,0 +1,230 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use super::{Context, Evaluator, Program, check_program, print_result};
+use crate::diagnostics::{Diagnostics, Stage, truncate_capture};
+use crate::diff;
+use crate::libtest::{TestCase, TestStatus};
+use crate::{ExerciseOutput, Outcome, StageFailure, exercise_file};
+
+// 输出中的数字，float_tolerance 不为空时按数值比较
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[-+]?(\d+\.?\d*|\.\d+)([eE][-+]?\d+)?").unwrap());
+
+// 运行程序的 main 函数，并将标准输出与期望输出文件比较。path 可以是单文件程序或 Cargo 项目，
+// 例如 `{ "type": "output_compare", "path": "normal/solution1", "expected": "normal/solution1.out" }`。
+// 也可以作为 cargo_project 的 `output` 字段，在测试与 clippy 通过后再比较输出
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct OutputCompare {
+    // 期望输出文件，相对于 exercises/ 目录
+    pub expected: String,
+    // 传给程序的命令行参数
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    // 作为标准输入的文件，相对于 exercises/ 目录；为空时标准输入为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stdin: Option<String>,
+    #[serde(default)]
+    pub normalize: Normalize,
+}
+
+// 比较之前对两边输出所做的处理；换行符 \r\n 总是视为 \n
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct Normalize {
+    // 去掉每行首尾的空白以及开头和末尾的空行
+    pub trim_whitespace: bool,
+    // 忽略末尾的换行
+    pub ignore_trailing_newline: bool,
+    // 数字之间允许的最大绝对误差，其余部分仍需完全一致
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub float_tolerance: Option<f64>,
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Normalize { trim_whitespace: false, ignore_trailing_newline: true, float_tolerance: None }
+    }
+}
+
+impl Normalize {
+    fn apply(&self, text: &str) -> String {
+        let mut text = text.replace("\r\n", "\n");
+        if self.trim_whitespace {
+            let lines: Vec<&str> = text.lines().map(str::trim).collect();
+            text = lines.join("\n").trim_matches('\n').to_string();
+        }
+        if self.ignore_trailing_newline {
+            text.truncate(text.trim_end_matches('\n').len());
+        }
+        text
+    }
+
+    // 两行是否视为相同
+    fn same_line(&self, expected: &str, actual: &str) -> bool {
+        let Some(tolerance) = self.float_tolerance else {
+            return expected == actual;
+        };
+
+        // 去掉数字后的部分必须完全一致，数字逐个比较
+        let skeleton = |line| NUMBER.replace_all(line, "0").into_owned();
+        let numbers = |line| NUMBER.find_iter(line).map(|number| number.as_str().parse::<f64>()).collect::<Vec<_>>();
+        skeleton(expected) == skeleton(actual)
+            && numbers(expected).into_iter().zip(numbers(actual)).all(|pair| match pair {
+                (Ok(expected), Ok(actual)) => (expected - actual).abs() <= tolerance,
+                _ => false,
+            })
+    }
+}
+
+impl OutputCompare {
+    fn expected_path(&self) -> PathBuf {
+        exercise_file(&self.expected)
+    }
+
+    fn stdin_path(&self) -> Option<PathBuf> {
+        self.stdin.as_deref().map(exercise_file)
+    }
+}
+
+impl Evaluator for OutputCompare {
+    fn check(&self, path: &Path) -> Result<(), String> {
+        check_program(path)?;
+        self.check_files()
+    }
+
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let result = self.compare(context, output);
+        print_result(context.source, "", &result, output);
+        result
+    }
+
+    fn inputs(&self) -> Vec<PathBuf> {
+        [Some(self.expected_path()), self.stdin_path()].into_iter().flatten().collect()
+    }
+}
+
+impl OutputCompare {
+    // 检查期望输出、标准输入文件与比较选项
+    pub(super) fn check_files(&self) -> Result<(), String> {
+        if !self.expected_path().is_file() {
+            return Err(format!("expected output {} does not exist", self.expected_path().display()));
+        }
+        if let Some(stdin) = self.stdin_path().filter(|stdin| !stdin.is_file()) {
+            return Err(format!("stdin file {} does not exist", stdin.display()));
+        }
+        let tolerance = self.normalize.float_tolerance;
+        if let Some(tolerance) = tolerance.filter(|tolerance| !tolerance.is_finite() || *tolerance < 0.0) {
+            return Err(format!("normalize.float_tolerance must be a non-negative number, got {}", tolerance));
+        }
+        Ok(())
+    }
+
+    // 运行程序并比较输出，结果为一个名为 output 的测试；不一致时输出 unified diff
+    pub(super) fn compare(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let expected = read(&self.expected_path())?;
+        let stdin = self.stdin_path().map(|stdin| read(&stdin)).transpose()?;
+
+        let program = Program::build(context.path, context.build_dir, false, context.deadline)?;
+        let out = program.run(context, &self.args, stdin.as_ref().map(|stdin| stdin.as_bytes()))?;
+
+        let expected = self.normalize.apply(&expected);
+        let actual = self.normalize.apply(&String::from_utf8_lossy(&out.stdout));
+        let expected_lines: Vec<&str> = expected.split('\n').collect();
+        let actual_lines: Vec<&str> = actual.split('\n').collect();
+
+        let same = |expected: &str, actual: &str| self.normalize.same_line(expected, actual);
+        if expected_lines.len() == actual_lines.len()
+            && expected_lines.iter().zip(&actual_lines).all(|(expected, actual)| same(expected, actual))
+        {
+            return Ok(vec![TestCase {
+                name: "output".to_string(),
+                status: TestStatus::Passed,
+                message: None,
+                duration_ms: None,
+            }]);
+        }
+
+        let diff = diff::unified(&expected_lines, &actual_lines, same, "expected", "actual");
+        output.info(diff.trim_end().to_string());
+
+        let message = truncate_capture(
+            format!("stdout does not match {}\n{}", self.expected_path().display(), diff).as_bytes(),
+        );
+        Err(StageFailure {
+            outcome: Outcome::Failed,
+            diagnostics: Box::new(Diagnostics {
+                stage: Some(Stage::Run),
+                stderr: message.clone(),
+                failing_tests: vec!["output".to_string()],
+                ..Diagnostics::default()
+            }),
+            tests: vec![TestCase {
+                name: "output".to_string(),
+                status: TestStatus::Failed,
+                message: Some(message),
+                duration_ms: None,
+            }],
+        })
+    }
+}
+
+fn read(path: &Path) -> Result<String, StageFailure> {
+    fs::read_to_string(path).map_err(|e| StageFailure {
+        outcome: Outcome::Failed,
+        diagnostics: Box::new(Diagnostics {
+            stderr: format!("Failed to read {}: {}", path.display(), e),
+            ..Diagnostics::default()
+        }),
+        tests: Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trailing_newlines_are_ignored_by_default() {
+        let normalize = Normalize::default();
+        assert_eq!(normalize.apply("a\r\nb\r\n"), "a\nb");
+        assert_eq!(normalize.apply("a\nb"), normalize.apply("a\nb\n\n"));
+        assert_eq!(normalize.apply("  a \n"), "  a ");
+        assert_eq!(normalize.apply(""), "");
+        assert_eq!(normalize.apply("\n"), "");
+    }
+
+    #[test]
+    fn missing_final_newline_differs_when_not_ignored() {
+        let normalize = Normalize { ignore_trailing_newline: false, ..Normalize::default() };
+        assert_eq!(normalize.apply("a\r\nb\r\n"), "a\nb\n");
+        assert_ne!(normalize.apply("a\nb"), normalize.apply("a\nb\n"));
+        assert_eq!(normalize.apply(""), "");
+    }
+
+    #[test]
+    fn trim_whitespace_strips_lines_and_blank_edges() {
+        let normalize = Normalize { trim_whitespace: true, ..Normalize::default() };
+        assert_eq!(normalize.apply("\n  a  \n\tb\n\n \n"), "a\nb");
+        assert_eq!(normalize.apply(" a \n\n b "), "a\n\nb");
+        assert_eq!(normalize.apply(" \n\t\n"), "");
+    }
+
+    #[test]
+    fn numbers_are_compared_within_the_tolerance() {
+        let exact = Normalize::default();
+        assert!(exact.same_line("x = 1.0", "x = 1.0"));
+        assert!(!exact.same_line("x = 1.0", "x = 1"));
+
+        let normalize = Normalize { float_tolerance: Some(0.001), ..Normalize::default() };
+        assert!(normalize.same_line("x = 1.0, y = -2", "x = 1.0004, y = -2.0"));
+        assert!(normalize.same_line("1e3", "1000.0005"));
+        assert!(!normalize.same_line("x = 1.0", "x = 1.01"));
+        assert!(!normalize.same_line("x = 1.0", "y = 1.0"));
+        assert!(!normalize.same_line("1 2", "1"));
+        assert!(normalize.same_line("", ""));
+    }
+}
@@ -0,0 +1,55 @@
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+use super::{Context, Evaluator, check_rust_file, compile, print_result, run_tests, sandboxed};
+use crate::diagnostics::Stage;
+use crate::libtest::{self, TestCase};
+use crate::{ExerciseOutput, Outcome, StageFailure};
+
+// 单文件 Rust 习题：使用 rustc --test 编译并运行其中的测试
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SingleFile {}
+
+impl Evaluator for SingleFile {
+    fn check(&self, path: &Path) -> Result<(), String> {
+        check_rust_file(path)
+    }
+
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let file_path = context.path;
+
+        // 编译测试文件
+        let test_binary = match compile(file_path, context.build_dir, &["--test"], context.deadline) {
+            Ok(test_binary) => test_binary,
+            Err(failure) => {
+                if failure.outcome == Outcome::Failed {
+                    output.warn(format!("{}: COMPILATION FAILED", context.source.display()).red().to_string());
+                }
+                return Err(failure);
+            }
+        };
+
+        // 编译成功，运行测试二进制文件（默认在沙箱中运行）
+        let test_run = sandboxed(Stage::Test, context.limits, |sandbox| {
+            run_tests(
+                |json| {
+                    let mut command = Command::new(&test_binary);
+                    if let Some(sandbox) = sandbox {
+                        sandbox.apply(&mut command);
+                    }
+                    if json {
+                        libtest::request_json(&mut command);
+                    }
+                    command
+                },
+                context.deadline,
+            )
+        });
+
+        print_result(context.source, "TEST ", &test_run, output);
+        test_run
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use super::cargo_project::build_test_and_lint;
+use super::{Context, Evaluator, check_cargo_project, print_result};
+use crate::libtest::TestCase;
+use crate::{ExerciseOutput, StageFailure};
+
+// Cargo 工作区中的一个成员：path 指向工作区根目录，只构建、测试并检查 package 指定的成员，
+// 例如 `{ "type": "cargo_workspace_member", "path": "hard/workspace", "package": "solver" }`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspaceMember {
+    pub package: String,
+}
+
+impl Evaluator for WorkspaceMember {
+    fn check(&self, path: &Path) -> Result<(), String> {
+        check_cargo_project(path)?;
+        if self.package.trim().is_empty() {
+            return Err("package must not be empty".to_string());
+        }
+
+        let manifest = fs::read_to_string(path.join("Cargo.toml")).map_err(|e| e.to_string())?;
+        if !manifest.lines().any(|line| line.trim() == "[workspace]") {
+            return Err(format!("{} is not a Cargo workspace", path.display()));
+        }
+        Ok(())
+    }
+
+    fn evaluate(&self, context: &Context, output: &mut ExerciseOutput) -> Result<Vec<TestCase>, StageFailure> {
+        let result = build_test_and_lint(context, &["--package", &self.package]);
+        print_result(context.source, "", &result, output);
+        result
+    }
+
+    fn uses_clippy(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+// 评测时 cargo 生成的构建目录，遍历题目时跳过
+const TARGET_DIR: &str = "target";
+
+// 题目中的所有文件，按路径排序：单文件题目只有它自己，目录中跳过所有 target 子目录
+pub fn project_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if path.is_file() {
+        files.push(path.to_path_buf());
+    } else {
+        collect(path, &mut files)?;
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != TARGET_DIR) {
+                collect(&path, files)?;
+            }
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+// 把目录中除 target 以外的文件复制到 to 中对应的位置，覆盖已有的文件
+pub fn copy_tree(from: &Path, to: &Path) -> io::Result<()> {
+    copy_tree_with(from, to, |from, to| fs::copy(from, to).map(|_| ()))
+}
+
+// 与 copy_tree 相同，每个文件由 copy 写入目标位置（例如在复制时改写源码）
+pub fn copy_tree_with(from: &Path, to: &Path, copy: impl Fn(&Path, &Path) -> io::Result<()>) -> io::Result<()> {
+    fs::create_dir_all(to)?;
+    for file in project_files(from)? {
+        let target = to.join(file.strip_prefix(from).unwrap_or(&file));
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        copy(&file, &target)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_directories_are_skipped() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in ["src/main.rs", "Cargo.toml", "target/debug/main", "src/target/x.rs", "targets/a.rs"] {
+            fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            fs::write(root.join(file), file).unwrap();
+        }
+
+        let files = project_files(root).unwrap();
+        let relative: Vec<_> = files.iter().map(|file| file.strip_prefix(root).unwrap().to_path_buf()).collect();
+        let expected: Vec<PathBuf> = ["Cargo.toml", "src/main.rs", "targets/a.rs"].iter().map(PathBuf::from).collect();
+        assert_eq!(relative, expected);
+        assert_eq!(project_files(&root.join("Cargo.toml")).unwrap(), [root.join("Cargo.toml")]);
+
+        let copy = tempfile::tempdir().unwrap();
+        let copy = copy.path();
+        copy_tree(root, copy).unwrap();
+        assert_eq!(fs::read_to_string(copy.join("src/main.rs")).unwrap(), "src/main.rs");
+        assert!(!copy.join("target").exists());
+    }
+}
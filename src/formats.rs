@@ -0,0 +1,343 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::diagnostics::Stage;
+use crate::libtest::{TestCase, TestStatus};
+use crate::lints::LintLevel;
+use crate::{ExerciseResult, Outcome, Report};
+
+// 评测报告的输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Json,
+    Junit,
+    Tap,
+    Markdown,
+}
+
+impl Format {
+    // 未指定 --output 时使用的文件名
+    pub fn default_output(&self) -> &'static str {
+        match self {
+            Format::Json => "report.json",
+            Format::Junit => "report.xml",
+            Format::Tap => "report.tap",
+            Format::Markdown => "report.md",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "json" => Ok(Format::Json),
+            "junit" => Ok(Format::Junit),
+            "tap" => Ok(Format::Tap),
+            "markdown" => Ok(Format::Markdown),
+            _ => Err(format!("Unknown format '{}', expected one of: json, junit, tap, markdown", s)),
+        }
+    }
+}
+
+pub fn render(format: Format, report: &Report) -> Result<String, serde_json::Error> {
+    Ok(match format {
+        Format::Json => serde_json::to_string_pretty(report)?,
+        Format::Junit => junit(report),
+        Format::Tap => tap(report),
+        Format::Markdown => markdown(report),
+    })
+}
+
+// 报告中的一个测试用例：libtest 中的单个测试，或者没有运行到测试时代表失败阶段的虚拟用例
+struct Case<'a> {
+    name: &'a str,
+    status: TestStatus,
+    message: Option<&'a str>,
+    duration_ms: Option<f64>,
+}
+
+// 题目对应的测试用例。测试以外的阶段失败（编译、clippy、超时等）时追加一个以阶段命名的失败用例，
+// 这样每个未通过的题目至少包含一个失败用例
+fn cases(exercise: &ExerciseResult) -> Vec<Case<'_>> {
+    let mut cases: Vec<Case> = exercise
+        .tests
+        .iter()
+        .map(|test: &TestCase| Case {
+            name: &test.name,
+            status: test.status,
+            message: test.message.as_deref(),
+            duration_ms: test.duration_ms,
+        })
+        .collect();
+
+    // 进行中的题目不算失败，以一个跳过的用例表示
+    if exercise.outcome == Outcome::InProgress {
+        cases.push(Case {
+            name: stage_name(None, exercise.outcome),
+            status: TestStatus::Ignored,
+            message: Some("exercise is still marked as I AM NOT DONE"),
+            duration_ms: None,
+        });
+        return cases;
+    }
+
+    let has_failed_case = cases.iter().any(|case| case.status == TestStatus::Failed);
+    let stopped = matches!(exercise.outcome, Outcome::TimedOut | Outcome::SandboxViolation | Outcome::EnvironmentError);
+    if exercise.outcome != Outcome::Passed && (stopped || !has_failed_case) {
+        let diagnostics = exercise.diagnostics.as_ref();
+        cases.push(Case {
+            name: stage_name(diagnostics.and_then(|diagnostics| diagnostics.stage), exercise.outcome),
+            status: TestStatus::Failed,
+            message: diagnostics.map(|diagnostics| diagnostics.stderr.as_str()),
+            duration_ms: None,
+        });
+    }
+
+    if cases.is_empty() {
+        cases.push(Case { name: &exercise.name, status: TestStatus::Passed, message: None, duration_ms: None });
+    }
+
+    cases
+}
+
+fn stage_name(stage: Option<Stage>, outcome: Outcome) -> &'static str {
+    match (outcome, stage) {
+        (Outcome::TimedOut, _) => "timeout",
+        (Outcome::SandboxViolation, _) => "sandbox",
+        (Outcome::InProgress, _) => "in_progress",
+        (Outcome::EnvironmentError, _) => "environment",
+        (_, Some(stage)) => stage.as_str(),
+        (_, None) => "setup",
+    }
+}
+
+fn seconds(duration_ms: u64) -> String {
+    format!("{:.3}", duration_ms as f64 / 1000.0)
+}
+
+// JUnit XML：每道题对应一个 testsuite，每个测试对应一个 testcase
+fn junit(report: &Report) -> String {
+    let mut xml = String::new();
+    // 根元素的计数是各个 testsuite 中测试用例的总和，需要先生成所有 testsuite
+    let (mut total_tests, mut total_failures, mut total_errors) = (0, 0, 0);
+
+    for exercise in &report.exercises {
+        let cases = cases(exercise);
+        let count = |status| cases.iter().filter(|case| case.status == status).count();
+        // 环境问题在 JUnit 中记为 error（而不是 failure），表示测试没能正常运行
+        let environment = exercise.outcome == Outcome::EnvironmentError;
+        let failure = if environment { "error" } else { "failure" };
+        let failed = count(TestStatus::Failed);
+        let (failures, errors) = if environment { (0, failed) } else { (failed, 0) };
+        total_tests += cases.len();
+        total_failures += failures;
+        total_errors += errors;
+
+        writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" errors="{}" skipped="{}" time="{}">"#,
+            escape_xml(&exercise.name),
+            cases.len(),
+            failures,
+            errors,
+            count(TestStatus::Ignored),
+            seconds(exercise.duration_ms)
+        )
+        .ok();
+        writeln!(
+            xml,
+            r#"    <properties><property name="outcome" value="{}"/><property name="score" value="{}"/></properties>"#,
+            exercise.outcome.as_str(),
+            exercise.score
+        )
+        .ok();
+
+        for case in &cases {
+            let mut open = format!(
+                r#"    <testcase classname="{}" name="{}""#,
+                escape_xml(&exercise.name),
+                escape_xml(case.name)
+            );
+            if let Some(duration_ms) = case.duration_ms {
+                write!(open, r#" time="{:.6}""#, duration_ms / 1000.0).ok();
+            }
+            match case.status {
+                TestStatus::Passed => writeln!(xml, "{}/>", open).ok(),
+                TestStatus::Ignored => writeln!(xml, "{}><skipped/></testcase>", open).ok(),
+                TestStatus::Failed => {
+                    let message = case.message.unwrap_or_default();
+                    let summary = message.lines().find(|line| !line.trim().is_empty()).unwrap_or(case.name);
+                    writeln!(
+                        xml,
+                        r#"{}><{} message="{}">{}</{}></testcase>"#,
+                        open,
+                        failure,
+                        escape_xml(summary),
+                        escape_xml(message),
+                        failure
+                    )
+                    .ok()
+                }
+            };
+        }
+
+        writeln!(xml, "  </testsuite>").ok();
+    }
+
+    let mut header = String::new();
+    writeln!(header, r#"<?xml version="1.0" encoding="UTF-8"?>"#).ok();
+    writeln!(
+        header,
+        r#"<testsuites name="exercises" tests="{}" failures="{}" errors="{}" time="{}">"#,
+        total_tests,
+        total_failures,
+        total_errors,
+        report.statistics.total_time
+    )
+    .ok();
+    writeln!(xml, "</testsuites>").ok();
+    header + &xml
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // XML 1.0 不允许出现的控制字符（例如终端颜色代码中的 ESC）
+            c if (c as u32) < 0x20 && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// TAP version 13：每道题是一个测试点，单个测试作为缩进的子测试
+fn tap(report: &Report) -> String {
+    let mut tap = String::new();
+
+    writeln!(tap, "TAP version 13").ok();
+    writeln!(tap, "1..{}", report.exercises.len()).ok();
+
+    for (index, exercise) in report.exercises.iter().enumerate() {
+        let cases = cases(exercise);
+        writeln!(tap, "    # Subtest: {}", exercise.name).ok();
+        writeln!(tap, "    1..{}", cases.len()).ok();
+        for (case_index, case) in cases.iter().enumerate() {
+            let line = match case.status {
+                TestStatus::Passed => match case.duration_ms {
+                    Some(duration_ms) => format!("ok {} - {} # time={}ms", case_index + 1, case.name, duration_ms),
+                    None => format!("ok {} - {}", case_index + 1, case.name),
+                },
+                TestStatus::Failed => format!("not ok {} - {}", case_index + 1, case.name),
+                TestStatus::Ignored => format!("ok {} - {} # SKIP", case_index + 1, case.name),
+            };
+            writeln!(tap, "    {}", line).ok();
+        }
+
+        let status = if exercise.result { "ok" } else { "not ok" };
+        writeln!(tap, "{} {} - {}", status, index + 1, exercise.name).ok();
+        writeln!(tap, "  ---").ok();
+        writeln!(tap, "  outcome: {}", exercise.outcome.as_str()).ok();
+        writeln!(tap, "  score: {}", exercise.score).ok();
+        writeln!(tap, "  duration_ms: {}", exercise.duration_ms).ok();
+        if let Some(diagnostics) = &exercise.diagnostics {
+            if let Some(stage) = diagnostics.stage {
+                writeln!(tap, "  stage: {}", stage.as_str()).ok();
+            }
+            if !diagnostics.stderr.is_empty() {
+                writeln!(tap, "  stderr: |").ok();
+                for line in diagnostics.stderr.lines() {
+                    writeln!(tap, "    {}", line).ok();
+                }
+            }
+        }
+        writeln!(tap, "  ...").ok();
+    }
+
+    tap
+}
+
+// Markdown：总览表格，未通过的题目附带失败详情，最后列出 clippy 发现的问题
+fn markdown(report: &Report) -> String {
+    let stats = &report.statistics;
+    let mut md = String::new();
+
+    writeln!(md, "# Grading report\n").ok();
+    writeln!(
+        md,
+        "**Score:** {} · **Passed:** {}/{} · **Time:** {}s\n",
+        stats.total_score, stats.total_successes, stats.total_exercises, stats.total_time
+    )
+    .ok();
+    if let Some(toolchain) = &report.toolchain {
+        let clippy = toolchain.clippy.as_deref().unwrap_or("clippy not installed");
+        writeln!(md, "**Toolchain:** {} · {} · {}\n", toolchain.rustc, toolchain.cargo, clippy).ok();
+    }
+    writeln!(md, "| Exercise | Result | Score | Tests | Time |").ok();
+    writeln!(md, "| --- | --- | ---: | ---: | ---: |").ok();
+
+    for exercise in &report.exercises {
+        let result = match exercise.outcome {
+            Outcome::Passed => "✅ passed",
+            Outcome::Failed => "❌ failed",
+            Outcome::TimedOut => "⏱ timed out",
+            Outcome::SandboxViolation => "🚫 sandbox violation",
+            Outcome::InProgress => "🚧 in progress",
+            Outcome::EnvironmentError => "⚠️ environment error",
+        };
+        let passed = exercise.tests.iter().filter(|test| test.status == TestStatus::Passed).count();
+        writeln!(
+            md,
+            "| {} | {} | {} | {}/{} | {}s |",
+            exercise.name.replace('|', "\\|"),
+            result,
+            exercise.score,
+            passed,
+            exercise.tests.len(),
+            seconds(exercise.duration_ms)
+        )
+        .ok();
+    }
+
+    let failures: Vec<_> = report.exercises.iter().filter(|exercise| !exercise.result).collect();
+    if !failures.is_empty() {
+        writeln!(md, "\n## Failures").ok();
+    }
+    for exercise in failures {
+        writeln!(md, "\n### {}\n", exercise.name).ok();
+        for case in cases(exercise).iter().filter(|case| case.status == TestStatus::Failed) {
+            writeln!(md, "- `{}`", case.name).ok();
+            if let Some(message) = case.message.filter(|message| !message.is_empty()) {
+                writeln!(md, "\n```text\n{}\n```\n", message.trim_end()).ok();
+            }
+        }
+    }
+
+    let linted: Vec<_> = report.exercises.iter().filter(|exercise| !exercise.lints.is_empty()).collect();
+    if !linted.is_empty() {
+        writeln!(md, "\n## Clippy findings").ok();
+    }
+    for exercise in linted {
+        writeln!(md, "\n### {}\n", exercise.name).ok();
+        for finding in &exercise.lints {
+            writeln!(
+                md,
+                "- {} `{}`{}: {}",
+                if finding.level == LintLevel::Error { "❌" } else { "⚠️" },
+                finding.lint.as_deref().unwrap_or("rustc"),
+                finding.location().map_or(String::new(), |location| format!(" at `{}`", location)),
+                finding.message
+            )
+            .ok();
+        }
+    }
+
+    md
+}
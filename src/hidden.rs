@@ -0,0 +1,48 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::Exercise;
+use crate::files::{copy_tree, copy_tree_with};
+use crate::source::strip_test_modules;
+
+// 隐藏测试：评测方在 --hidden-tests 指定的目录中按照 exercises/ 的结构为题目提供测试，
+// 例如 `hidden/easy/algorithm11.rs`、`hidden/normal/solution1/src/tests.rs`。
+// 评测时在临时副本中删除学生自己的测试，再放入隐藏测试，学生对测试的修改不影响得分
+
+// 题目对应的隐藏测试（单文件题目为一个文件，Cargo 项目为一个目录），不存在时返回 None
+pub fn suite(root: &Path, exercise: &Exercise) -> Option<PathBuf> {
+    let suite = root.join(&exercise.path);
+    let matches_kind = if exercise.source_path().is_dir() { suite.is_dir() } else { suite.is_file() };
+    matches_kind.then_some(suite)
+}
+
+// 在 dest 中创建题目的副本并放入隐藏测试，返回副本的路径：
+// 单文件题目删除 `#[cfg(test)]` 模块后在末尾追加隐藏测试文件的内容；
+// Cargo 项目删除各源文件中的 `#[cfg(test)]` 模块与 tests/ 目录，再用隐藏测试目录中的文件覆盖
+pub fn overlay(source: &Path, suite: &Path, dest: &Path) -> io::Result<PathBuf> {
+    fs::create_dir_all(dest)?;
+    let copy = dest.join(source.file_name().unwrap_or_default());
+
+    if source.is_file() {
+        let student = fs::read_to_string(source)?;
+        let tests = fs::read_to_string(suite)?;
+        fs::write(&copy, format!("{}\n{}", strip_test_modules(&student), tests))?;
+        return Ok(copy);
+    }
+
+    // 复制项目时删除 .rs 文件中的测试模块
+    copy_tree_with(source, &copy, |from, to| {
+        if from.extension().is_some_and(|extension| extension == "rs") {
+            fs::write(to, strip_test_modules(&fs::read_to_string(from)?))
+        } else {
+            fs::copy(from, to).map(|_| ())
+        }
+    })?;
+    let student_tests = copy.join("tests");
+    if student_tests.is_dir() {
+        fs::remove_dir_all(student_tests)?;
+    }
+    copy_tree(suite, &copy)?;
+    Ok(copy)
+}
@@ -0,0 +1,96 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::{Exercise, exercise_file};
+
+// 每多失败这么多次解锁下一条提示
+pub const FAILURES_PER_HINT: usize = 2;
+
+// exercise_config.json 中的 `hint` 字段：一条提示、按顺序解锁的多条提示，
+// 或者 `{ "file": "hints/algorithm14.md" }`，文件中用单独一行 `---` 分隔各条提示
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum Hint {
+    Text(String),
+    Staged(Vec<String>),
+    File { file: String },
+}
+
+impl Hint {
+    // 提示文件，相对于 exercises/ 目录
+    pub fn file_path(&self) -> Option<PathBuf> {
+        match self {
+            Hint::File { file } => Some(exercise_file(file)),
+            _ => None,
+        }
+    }
+
+    // 按解锁顺序排列的各条提示
+    pub fn stages(&self) -> Result<Vec<String>, String> {
+        let stages = match self {
+            Hint::Text(text) => vec![text.clone()],
+            Hint::Staged(stages) => stages.clone(),
+            Hint::File { .. } => {
+                let path = self.file_path().unwrap_or_default();
+                let content = fs::read_to_string(&path).map_err(|e| format!("failed to read {}: {}", path.display(), e))?;
+                let mut stages = vec![String::new()];
+                for line in content.lines() {
+                    if line.trim() == "---" {
+                        stages.push(String::new());
+                    } else {
+                        let stage = stages.last_mut().unwrap();
+                        stage.push_str(line);
+                        stage.push('\n');
+                    }
+                }
+                stages
+            }
+        };
+
+        let stages: Vec<String> = stages.iter().map(|stage| stage.trim().to_string()).filter(|stage| !stage.is_empty()).collect();
+        if stages.is_empty() {
+            return Err("hint must not be empty".to_string());
+        }
+        Ok(stages)
+    }
+}
+
+// 题目当前可以看到的提示。第一条提示总是可见，之后每失败 FAILURES_PER_HINT 次解锁一条；
+// 没有配置提示时使用题目开头注释中的说明
+pub fn lines(exercise: &Exercise, failures: usize) -> Vec<String> {
+    let Some(hint) = &exercise.hint else {
+        return match header_comment(exercise) {
+            Some(comment) => comment.lines().map(String::from).collect(),
+            None => vec![format!("No hint available for {}", exercise.name)],
+        };
+    };
+
+    let stages = match hint.stages() {
+        Ok(stages) => stages,
+        Err(e) => return vec![format!("Failed to load hints for {}: {}", exercise.name, e)],
+    };
+    let unlocked = (1 + failures / FAILURES_PER_HINT).min(stages.len());
+
+    let mut lines = Vec::new();
+    for (index, stage) in stages.iter().take(unlocked).enumerate() {
+        lines.push(format!("Hint {}/{}:", index + 1, stages.len()));
+        lines.extend(stage.lines().map(String::from));
+        lines.push(String::new());
+    }
+    if unlocked < stages.len() {
+        let remaining = unlocked * FAILURES_PER_HINT - failures;
+        lines.push(format!("The next hint unlocks after {} more failed attempt(s)", remaining));
+    } else {
+        lines.pop();
+    }
+    lines
+}
+
+// 题目开头 `/* ... */` 注释中的题目说明与提示
+fn header_comment(exercise: &Exercise) -> Option<String> {
+    let source = fs::read_to_string(exercise.source_path()).ok()?;
+    let comment = source.trim_start().strip_prefix("/*")?;
+    let end = comment.find("*/")?;
+    Some(comment[..end].trim_matches('\n').to_string())
+}
@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Outcome;
+
+// 本地评测历史，每次评测追加一行 JSON
+pub const HISTORY_FILE: &str = ".grader-history.jsonl";
+
+// 一次评测运行
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Run {
+    // Unix 时间戳（秒）
+    pub timestamp: u64,
+    // 评测时仓库 HEAD 的提交（短哈希），不在 git 仓库中时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    pub exercises: Vec<Attempt>,
+}
+
+// 一次运行中一道题的结果
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Attempt {
+    pub name: String,
+    pub outcome: Outcome,
+    pub score: f64,
+}
+
+impl Run {
+    pub fn now(exercises: Vec<Attempt>) -> Self {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        Run { timestamp, commit: git_commit(), exercises }
+    }
+
+    // 本次运行的总分
+    // 与 scoring::score 相同保留两位小数，避免部分得分相加后出现 0.32999999999999996 这样的误差
+    pub fn score(&self) -> f64 {
+        let score: f64 = self.exercises.iter().map(|attempt| attempt.score).sum();
+        (score * 100.0).round() / 100.0
+    }
+
+    pub fn passed(&self) -> usize {
+        self.exercises.iter().filter(|attempt| attempt.outcome == Outcome::Passed).count()
+    }
+
+    // 两次运行是否评测了同样的题目，只有这样分数才能直接比较
+    pub fn same_exercises(&self, other: &Run) -> bool {
+        let mut names: Vec<&str> = self.exercises.iter().map(|attempt| attempt.name.as_str()).collect();
+        let mut other_names: Vec<&str> = other.exercises.iter().map(|attempt| attempt.name.as_str()).collect();
+        names.sort_unstable();
+        other_names.sort_unstable();
+        names == other_names
+    }
+}
+
+// 一道题在上一次评测时通过，这一次却没有通过
+#[derive(Debug, Clone)]
+pub struct Regression<'a> {
+    pub name: &'a str,
+    pub run: &'a Run,
+    pub outcome: Outcome,
+}
+
+// 一道题第一次通过前的尝试次数与时间
+#[derive(Debug, Clone)]
+pub struct FirstPass<'a> {
+    pub name: &'a str,
+    // 到第一次通过为止（含）的评测次数，还未通过时为全部评测次数
+    pub attempts: usize,
+    pub first_attempt: u64,
+    pub passed: Option<u64>,
+}
+
+impl FirstPass<'_> {
+    // 从第一次评测到第一次通过经过的秒数
+    pub fn elapsed(&self) -> Option<u64> {
+        self.passed.map(|passed| passed.saturating_sub(self.first_attempt))
+    }
+}
+
+fn git_commit() -> Option<String> {
+    let output = Command::new("git").args(["rev-parse", "--short", "HEAD"]).output().ok()?;
+    let commit = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !commit.is_empty()).then_some(commit)
+}
+
+pub fn append(path: &str, run: &Run) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", serde_json::to_string(run)?)
+}
+
+// 读取所有运行记录，文件不存在时为空；无法解析的行（例如写入一半被中断）被跳过
+pub fn load(path: &str) -> io::Result<Vec<Run>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e),
+    }
+}
+
+// 题目自最近一次通过以来未通过的次数。还没有删除完成标记（学生还没有开始尝试）与环境问题不算一次失败
+pub fn failures_since_pass(runs: &[Run], name: &str) -> usize {
+    runs.iter()
+        .flat_map(|run| run.exercises.iter().filter(|attempt| attempt.name == name))
+        .fold(0, |failures, attempt| match attempt.outcome {
+            Outcome::Passed => 0,
+            Outcome::InProgress | Outcome::EnvironmentError => failures,
+            _ => failures + 1,
+        })
+}
+
+// 按时间顺序列出所有退步：与该题上一次的评测结果相比，从通过变为未通过
+pub fn regressions(runs: &[Run]) -> Vec<Regression<'_>> {
+    let mut last: HashMap<&str, Outcome> = HashMap::new();
+    let mut regressions = Vec::new();
+    for run in runs {
+        for attempt in &run.exercises {
+            let previous = last.insert(&attempt.name, attempt.outcome);
+            if previous == Some(Outcome::Passed) && attempt.outcome != Outcome::Passed {
+                regressions.push(Regression { name: &attempt.name, run, outcome: attempt.outcome });
+            }
+        }
+    }
+    regressions
+}
+
+// 每道题第一次通过的情况，按第一次评测的顺序排列
+pub fn first_passes(runs: &[Run]) -> Vec<FirstPass<'_>> {
+    let mut passes: Vec<FirstPass> = Vec::new();
+    for run in runs {
+        for attempt in &run.exercises {
+            let index = match passes.iter().position(|pass| pass.name == attempt.name) {
+                Some(index) => index,
+                None => {
+                    passes.push(FirstPass { name: &attempt.name, attempts: 0, first_attempt: run.timestamp, passed: None });
+                    passes.len() - 1
+                }
+            };
+            let pass = &mut passes[index];
+            if pass.passed.is_none() {
+                pass.attempts += 1;
+                if attempt.outcome == Outcome::Passed {
+                    pass.passed = Some(run.timestamp);
+                }
+            }
+        }
+    }
+    passes
+}
+
+// Unix 时间戳格式化为 UTC 时间，形如 `2026-10-18 09:30`
+pub fn format_timestamp(timestamp: u64) -> String {
+    let (year, month, day) = civil_from_days((timestamp / 86400) as i64);
+    let time = timestamp % 86400;
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, time / 3600, time % 3600 / 60)
+}
+
+// 时间间隔格式化为 `2d 3h`、`3h 15m`、`5m` 这样的形式
+pub fn format_duration(secs: u64) -> String {
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        "<1m".to_string()
+    }
+}
+
+// 1970-01-01 以来的天数转换为公历日期（Howard Hinnant 的 civil_from_days 算法）
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(outcomes: &[(&str, Outcome)]) -> Run {
+        let exercises =
+            outcomes.iter().map(|&(name, outcome)| Attempt { name: name.to_string(), outcome, score: 0.0 }).collect();
+        Run { timestamp: 0, commit: None, exercises }
+    }
+
+    #[test]
+    fn failures_are_counted_since_the_last_pass() {
+        let runs = [
+            run(&[("a", Outcome::Failed), ("b", Outcome::Failed)]),
+            run(&[("a", Outcome::Passed)]),
+            run(&[("a", Outcome::Failed), ("b", Outcome::TimedOut)]),
+            run(&[("a", Outcome::SandboxViolation)]),
+        ];
+        assert_eq!(failures_since_pass(&runs, "a"), 2);
+        assert_eq!(failures_since_pass(&runs, "b"), 2);
+        assert_eq!(failures_since_pass(&runs, "c"), 0);
+    }
+
+    #[test]
+    fn in_progress_and_environment_errors_are_not_failures() {
+        let runs = [
+            run(&[("a", Outcome::InProgress)]),
+            run(&[("a", Outcome::InProgress)]),
+            run(&[("a", Outcome::EnvironmentError)]),
+            run(&[("a", Outcome::Failed)]),
+            run(&[("a", Outcome::InProgress)]),
+        ];
+        assert_eq!(failures_since_pass(&runs, "a"), 1);
+    }
+}
@@ -0,0 +1,303 @@
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use crate::cache::hex;
+use crate::files::project_files;
+use crate::source::{blank_non_code, matching_brace, test_modules};
+use crate::{Exercise, ExerciseConfig};
+
+// 受保护区域的哈希，与 exercise_config.json 放在同一目录
+const HASHES_FILE: &str = "exercise_hashes.json";
+
+// 函数签名的开头，签名到第一个 `{` 或 `;` 为止
+static FUNCTION: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bfn\s+(\w+)").unwrap());
+
+// 括号前多余的逗号
+static TRAILING_COMMA: LazyLock<Regex> = LazyLock::new(|| Regex::new(r",([)\]}>])").unwrap());
+
+// 包含函数的 impl、trait 与 mod 块的开头（从行首开始的条目），到 `{` 为止；`mod name;` 没有块
+static ITEM_BLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)^[ \t]*(?:pub(?:\([^)]*\))?[ \t]+)?(?:unsafe[ \t]+)?((?:impl|trait|mod)\b[^{;]*)([{;])").unwrap()
+});
+
+// 测试模块的名字
+static MODULE_NAME: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\bmod\s+(\w+)").unwrap());
+
+// 按值传递的参数前的 `mut`（例如 `fn f(mut n: u32)`、`fn f(mut self)`），只影响函数体，不属于函数的接口
+static BINDING_MUT: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"([(,]\s*)mut\s+(\w+\s*[:,)])").unwrap());
+
+// 每道题各个受保护区域的 SHA-256，例如 `{ "algorithm11.rs": { "fn find_missing_number": "…" } }`
+pub type Hashes = BTreeMap<String, BTreeMap<String, String>>;
+
+// 配置文件对应的哈希文件：与配置文件在同一目录
+pub fn path(config: &Path) -> PathBuf {
+    config.parent().unwrap_or(Path::new("")).join(HASHES_FILE)
+}
+
+// 读取哈希文件，文件不存在时返回 None，此时不检查受保护区域
+pub fn load(path: &Path) -> io::Result<Option<Hashes>> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(serde_json::from_str(&content)?)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+// 根据 root 目录（题目模板或题目目录）中的文件重新计算所有题目的受保护区域
+pub fn record(config: &ExerciseConfig, root: &Path) -> io::Result<Hashes> {
+    config
+        .exercises()
+        .map(|(_, exercise)| {
+            let path = root.join(&exercise.path);
+            let regions = regions(&path)
+                .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path.display(), e)))?;
+            Ok((exercise.name.clone(), regions))
+        })
+        .collect()
+}
+
+pub fn save(path: &Path, hashes: &Hashes) -> io::Result<()> {
+    fs::write(path, serde_json::to_string_pretty(hashes)? + "\n")
+}
+
+// 与记录的哈希比较，返回被修改或删除的区域；哈希文件中没有这道题时不检查
+pub fn modified_regions(hashes: &Hashes, exercise: &Exercise) -> io::Result<Vec<String>> {
+    let Some(expected) = hashes.get(&exercise.name) else {
+        return Ok(Vec::new());
+    };
+
+    let actual = regions(&exercise.source_path())?;
+    Ok(expected
+        .iter()
+        .filter(|(region, hash)| actual.get(*region) != Some(*hash))
+        .map(|(region, _)| region.clone())
+        .collect())
+}
+
+// 区域是否是测试代码（测试模块、tests.rs 或 tests/ 下的文件），使用隐藏测试时这些区域会被替换，不需要检查
+pub fn is_test_region(region: &str) -> bool {
+    let name = region.rsplit(": ").next().unwrap_or(region);
+    name.starts_with("mod ") || region.starts_with("tests/") || region.ends_with("tests.rs")
+}
+
+// 题目中的受保护区域：Cargo 项目中名为 tests.rs 的文件与 tests/ 目录下的文件整体受保护，
+// 其余 .rs 文件（以及单文件题目）中的 `#[cfg(test)]` 模块与函数签名受保护。
+// 哈希由题目模板计算，学生自己添加的函数不在其中，不会被检查
+fn regions(path: &Path) -> io::Result<BTreeMap<String, String>> {
+    let mut regions = BTreeMap::new();
+    if path.is_file() {
+        add_source_regions(&fs::read_to_string(path)?, "", &mut regions);
+        return Ok(regions);
+    }
+
+    // 项目中的所有 .rs 文件，按路径排序
+    let sources = project_files(path)?
+        .into_iter()
+        .filter(|file| file.extension().is_some_and(|extension| extension == "rs"));
+    for file in sources {
+        let relative = file.strip_prefix(path).unwrap_or(&file).to_string_lossy().replace('\\', "/");
+        let source = fs::read_to_string(&file)?;
+        if relative.starts_with("tests/") || file.ends_with("tests.rs") {
+            regions.insert(relative, hash(&source));
+        } else {
+            add_source_regions(&source, &format!("{}: ", relative), &mut regions);
+        }
+    }
+    Ok(regions)
+}
+
+// 函数以所在的 impl、trait 与 mod 块标识，例如 `impl Stack: fn new`，在文件中增删其他块不会改变它的名字。
+// 函数体内部定义的函数是实现的一部分，不受保护
+fn add_source_regions(source: &str, prefix: &str, regions: &mut BTreeMap<String, String>) {
+    let code = blank_non_code(source);
+    let modules = test_modules(source);
+    let blocks = item_blocks(&code);
+    let mut insert = |label: String, text: &str| {
+        // 仍然同名的区域（例如被不同 cfg 选择的两个定义）按出现顺序编号
+        let mut key = format!("{}{}", prefix, label);
+        let mut index = 1;
+        while regions.contains_key(&key) {
+            index += 1;
+            key = format!("{}{} ({})", prefix, label, index);
+        }
+        regions.insert(key, hash(text));
+    };
+
+    for module in &modules {
+        let name = MODULE_NAME
+            .captures(&code[module.clone()])
+            .map_or("tests", |captures| captures.get(1).unwrap().as_str());
+        insert(format!("mod {}", name), &source[module.clone()]);
+    }
+
+    // 上一个函数体的结尾，在此之前的函数都是嵌套的
+    let mut body_end = 0;
+    for function in FUNCTION.captures_iter(&code) {
+        let start = function.get(0).unwrap().start();
+        if start < body_end || modules.iter().any(|module| module.contains(&start)) {
+            continue;
+        }
+        let end = code[start..].find(['{', ';']).map_or(code.len(), |offset| start + offset);
+        if code[end..].starts_with('{') {
+            body_end = matching_brace(&code, end).unwrap_or(code.len());
+        }
+        let mut label: Vec<&str> =
+            blocks.iter().filter(|(block, _)| block.contains(&start)).map(|(_, header)| header.as_str()).collect();
+        let name = format!("fn {}", &function[1]);
+        label.push(&name);
+        insert(label.join(": "), &BINDING_MUT.replace_all(&code[start..end], "$1$2"));
+    }
+}
+
+// 代码中 impl、trait 与 mod 块的字节范围（从 `{` 到匹配的 `}`）与空白规范化后的开头，按出现顺序排列
+fn item_blocks(code: &str) -> Vec<(Range<usize>, String)> {
+    ITEM_BLOCK
+        .captures_iter(code)
+        .filter(|block| &block[2] == "{")
+        .map(|block| {
+            let open = block.get(2).unwrap().start();
+            let close = matching_brace(code, open).unwrap_or(code.len());
+            // 与 hash 一样忽略空白与结尾逗号的差异（rustfmt 会给多行的 where 子句加上结尾逗号）
+            let header = block[1].split_whitespace().collect::<Vec<_>>().join(" ");
+            let header = TRAILING_COMMA.replace_all(header.trim_end_matches(','), "$1").into_owned();
+            (open..close, header)
+        })
+        .collect()
+}
+
+// 忽略空白与结尾逗号的差异，用 rustfmt 重新格式化代码不算修改
+fn hash(text: &str) -> String {
+    // 只保留两个标识符（或关键字、数字）之间必需的空格
+    let mut normalized = String::with_capacity(text.len());
+    for word in text.split_whitespace() {
+        let is_ident = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        if is_ident(normalized.chars().next_back()) && is_ident(word.chars().next()) {
+            normalized.push(' ');
+        }
+        normalized.push_str(word);
+    }
+    let normalized = TRAILING_COMMA.replace_all(&normalized, "$1");
+    hex(&Sha256::digest(normalized.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source_regions(source: &str) -> BTreeMap<String, String> {
+        let mut regions = BTreeMap::new();
+        add_source_regions(source, "", &mut regions);
+        regions
+    }
+
+    const EXERCISE: &str = r#"
+pub fn fib(n: i32) -> i32 {
+    fn multiply(a: i32, b: i32) -> i32 {
+        a * b
+    }
+    multiply(n, 1)
+}
+
+struct Stack;
+
+impl Stack {
+    pub fn new() -> Self {
+        Stack
+    }
+}
+
+impl Default for Stack {
+    fn new() -> Self {
+        Stack
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fib() {
+        assert_eq!(fib(1), 1);
+    }
+}
+"#;
+
+    #[test]
+    fn regions_cover_signatures_and_test_modules() {
+        let regions = source_regions(EXERCISE);
+        let names: Vec<&str> = regions.keys().map(String::as_str).collect();
+        assert_eq!(names, ["fn fib", "impl Default for Stack: fn new", "impl Stack: fn new", "mod tests"]);
+    }
+
+    #[test]
+    fn adding_an_impl_above_does_not_rename_regions() {
+        // 学生在已有的 impl 之前新增了一个带 `fn new` 的 impl 与模块
+        let helper = "struct Helper;\n\nimpl Helper {\n    fn new() -> Self {\n        Helper\n    }\n}\n\n\
+                      mod util {\n    pub fn new() {}\n}\n\nstruct Stack;\n";
+        let extended = source_regions(&EXERCISE.replacen("struct Stack;\n", helper, 1));
+        for (region, hash) in source_regions(EXERCISE) {
+            assert_eq!(extended.get(&region), Some(&hash), "{}", region);
+        }
+        assert!(extended.contains_key("impl Helper: fn new"));
+        assert!(extended.contains_key("mod util: fn new"));
+    }
+
+    #[test]
+    fn impl_trait_in_signatures_is_not_a_block() {
+        let source = "pub fn evens() -> impl Iterator<Item = u32> {\n    fn inner() {}\n    (0..).step_by(2)\n}\n";
+        let names: Vec<String> = source_regions(source).into_keys().collect();
+        assert_eq!(names, ["fn evens"]);
+    }
+
+    #[test]
+    fn nested_functions_are_not_protected() {
+        let renamed = EXERCISE.replace("multiply", "mul");
+        assert_eq!(source_regions(EXERCISE), source_regions(&renamed));
+    }
+
+    #[test]
+    fn function_bodies_are_not_protected() {
+        let solved = EXERCISE.replace("multiply(n, 1)", "if n < 2 { n } else { fib(n - 1) + fib(n - 2) }");
+        assert_eq!(source_regions(EXERCISE), source_regions(&solved));
+    }
+
+    #[test]
+    fn formatting_and_binding_mut_are_ignored() {
+        let reformatted = EXERCISE.replace("pub fn fib(n: i32) -> i32 {", "pub fn fib(\n    mut n: i32,\n) -> i32 {");
+        assert_eq!(source_regions(EXERCISE), source_regions(&reformatted));
+    }
+
+    #[test]
+    fn changed_signatures_and_tests_are_detected() {
+        let regions = source_regions(EXERCISE);
+        let signature = source_regions(&EXERCISE.replace("fib(n: i32)", "fib(n: &mut i32)"));
+        assert_ne!(regions["fn fib"], signature["fn fib"]);
+        assert_eq!(regions["mod tests"], signature["mod tests"]);
+
+        let weakened = source_regions(&EXERCISE.replace("assert_eq!(fib(1), 1);", ""));
+        assert_ne!(regions["mod tests"], weakened["mod tests"]);
+    }
+
+    #[test]
+    fn test_regions_are_recognized() {
+        assert!(is_test_region("mod tests"));
+        assert!(is_test_region("src/lib.rs: mod tests (2)"));
+        assert!(is_test_region("src/tests.rs"));
+        assert!(is_test_region("tests/integration.rs"));
+        assert!(!is_test_region("fn tests"));
+        assert!(!is_test_region("src/lib.rs: fn module"));
+    }
+
+    #[test]
+    fn functions_in_comments_and_strings_are_ignored() {
+        let regions = source_regions("// fn old() {}\nfn f() -> &'static str { \"fn g() {\" }\n");
+        assert_eq!(regions.keys().collect::<Vec<_>>(), ["fn f"]);
+    }
+}
@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+// 让 libtest 以 JSON 事件的形式输出每个测试的结果与耗时
+const JSON_ARGS: [&str; 5] = ["-Z", "unstable-options", "--format", "json", "--report-time"];
+
+// 开始运行却没有结果的测试在报告中的说明
+const UNFINISHED: &str = "the test did not finish: the test binary crashed or was killed while running it";
+
+// 单个测试的运行结果
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Ignored,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TestCase {
+    pub name: String,
+    pub status: TestStatus,
+    // 失败测试的输出（panic 信息等）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    // 测试耗时（毫秒），只有 libtest 输出 JSON 事件时才有
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<f64>,
+}
+
+// 按状态统计的测试数量
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct TestSummary {
+    pub passed: usize,
+    pub failed: usize,
+    pub ignored: usize,
+}
+
+impl TestSummary {
+    pub fn add(&mut self, tests: &[TestCase]) {
+        for test in tests {
+            match test.status {
+                TestStatus::Passed => self.passed += 1,
+                TestStatus::Failed => self.failed += 1,
+                TestStatus::Ignored => self.ignored += 1,
+            }
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.ignored
+    }
+}
+
+// libtest 的 JSON 事件，例如
+// `{ "type": "test", "name": "tests::test_sum", "event": "ok", "exec_time": 0.000012 }`
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    event: String,
+    name: Option<String>,
+    // suite 的 started 事件中本次要运行的测试数量
+    test_count: Option<u32>,
+    exec_time: Option<f64>,
+    stdout: Option<String>,
+    message: Option<String>,
+}
+
+// 运行文档测试时 RUSTC_BOOTSTRAP 的取值。libtest 只检查这个变量是否存在，而 rustc 只在取值为 1
+// 或列出了正在编译的 crate 名时才启用不稳定特性，带连字符的值不会是任何 crate 名
+const DOC_BOOTSTRAP: &str = "cargotest-json";
+
+// 为编译好的测试程序追加输出 JSON 事件的参数。-Z unstable-options 在稳定版工具链上需要设置 RUSTC_BOOTSTRAP；
+// 这个变量不能传给 cargo test，否则题目代码也会在启用不稳定特性的情况下编译
+pub fn request_json(command: &mut Command) -> &mut Command {
+    command.env("RUSTC_BOOTSTRAP", "1").args(JSON_ARGS)
+}
+
+// 为 cargo test --doc 追加输出 JSON 事件的参数。文档测试由 rustdoc 在运行时编译，
+// 只能把 RUSTC_BOOTSTRAP 设置为不会启用不稳定特性的值
+pub fn request_doc_json(command: &mut Command) -> &mut Command {
+    command.env("RUSTC_BOOTSTRAP", DOC_BOOTSTRAP).arg("--").args(JSON_ARGS)
+}
+
+// 测试程序不接受 JSON 参数时（例如工具链禁用了不稳定选项），需要去掉这些参数重新运行
+pub fn json_unsupported(stderr: &[u8]) -> bool {
+    String::from_utf8_lossy(stderr).contains("is only accepted on the nightly compiler")
+}
+
+// 解析 libtest 的输出，支持 JSON 事件与普通文本（例如 `test tests::test_sum ... ok`）两种格式；
+// cargo test 会依次运行多个测试程序，所有测试程序的结果都会被收集
+pub fn parse_output(stdout: &[u8]) -> Vec<TestCase> {
+    let stdout = String::from_utf8_lossy(stdout);
+
+    let mut started = Vec::new();
+    let mut tests = Vec::new();
+    for line in stdout.lines() {
+        if let Some(name) = started_test(line) {
+            started.push(name);
+        } else if let Some(test) = parse_event(line).or_else(|| parse_line(line)) {
+            tests.push(test);
+        }
+    }
+
+    // 开始运行却没有结果的测试：测试程序在运行它时崩溃（例如栈溢出）或被杀死，视为未通过
+    for name in started {
+        if !tests.iter().any(|test| test.name == name) {
+            tests.push(TestCase {
+                name,
+                status: TestStatus::Failed,
+                message: Some(UNFINISHED.to_string()),
+                duration_ms: None,
+            });
+        }
+    }
+
+    for (name, message) in failure_sections(&stdout) {
+        if let Some(test) = tests.iter_mut().find(|test| test.name == name && test.message.is_none()) {
+            test.message = Some(message);
+        }
+    }
+
+    tests
+}
+
+// 测试程序宣布要运行的测试总数（JSON 的 suite started 事件或文本中的 `running 3 tests`），没有时返回 None。
+// 测试程序中途崩溃时，还没有开始运行的测试不会出现在输出中，计分时需要用这个数量作为总数
+pub fn expected_count(stdout: &[u8]) -> Option<u32> {
+    let stdout = String::from_utf8_lossy(stdout);
+    let counts: Vec<u32> = stdout.lines().filter_map(suite_count).collect();
+    (!counts.is_empty()).then(|| counts.iter().sum())
+}
+
+fn suite_count(line: &str) -> Option<u32> {
+    if line.starts_with('{') {
+        let event: Event = serde_json::from_str(line).ok()?;
+        return if event.kind == "suite" && event.event == "started" { event.test_count } else { None };
+    }
+    let rest = line.strip_prefix("running ")?;
+    rest.strip_suffix(" tests").or_else(|| rest.strip_suffix(" test"))?.parse().ok()
+}
+
+// JSON 格式中测试开始运行的事件，返回测试名
+fn started_test(line: &str) -> Option<String> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    let event: Event = serde_json::from_str(line).ok()?;
+    if event.kind == "test" && event.event == "started" { event.name } else { None }
+}
+
+fn parse_event(line: &str) -> Option<TestCase> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    let event: Event = serde_json::from_str(line).ok()?;
+    if event.kind != "test" {
+        return None;
+    }
+
+    let status = match event.event.as_str() {
+        "ok" => TestStatus::Passed,
+        "failed" => TestStatus::Failed,
+        "ignored" => TestStatus::Ignored,
+        // started、timeout（运行时间过长的提醒）等事件
+        _ => return None,
+    };
+    let message = [event.stdout, event.message]
+        .into_iter()
+        .flatten()
+        .map(|text| text.trim().to_string())
+        .filter(|text| !text.is_empty())
+        .reduce(|stdout, message| format!("{}\n{}", stdout, message));
+
+    Some(TestCase {
+        name: event.name?,
+        status,
+        message: if status == TestStatus::Failed { message } else { None },
+        // 保留三位小数
+        duration_ms: event.exec_time.map(|seconds| (seconds * 1_000_000.0).round() / 1000.0),
+    })
+}
+
+fn parse_line(line: &str) -> Option<TestCase> {
+    let (name, result) = line.strip_prefix("test ")?.split_once(" ... ")?;
+    let status = if result == "ok" {
+        TestStatus::Passed
+    } else if result == "FAILED" {
+        TestStatus::Failed
+    } else if result.starts_with("ignored") {
+        TestStatus::Ignored
+    } else {
+        return None;
+    };
+    Some(TestCase { name: name.to_string(), status, message: None, duration_ms: None })
+}
+
+// 提取 `failures:` 部分中每个失败测试的输出：
+//
+// ---- tests::test_sum stdout ----
+// thread 'tests::test_sum' panicked at ...
+fn failure_sections(stdout: &str) -> Vec<(String, String)> {
+    let mut sections = Vec::new();
+    let mut current: Option<(String, Vec<&str>)> = None;
+
+    for line in stdout.lines() {
+        let header = line
+            .strip_prefix("---- ")
+            .and_then(|rest| rest.strip_suffix(" ----"))
+            .and_then(|rest| rest.strip_suffix(" stdout"));
+
+        if header.is_some() || line == "failures:" {
+            if let Some((name, lines)) = current.take() {
+                sections.push((name, lines.join("\n").trim().to_string()));
+            }
+        }
+
+        if let Some(name) = header {
+            current = Some((name.to_string(), Vec::new()));
+        } else if let Some((_, lines)) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+
+    if let Some((name, lines)) = current {
+        sections.push((name, lines.join("\n").trim().to_string()));
+    }
+
+    sections
+}
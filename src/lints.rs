@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+// 题目的 clippy 规则，在 exercise_config.json 中通过 `clippy` 字段配置，例如
+// `"clippy": { "deny": ["clippy::pedantic"], "allow": ["clippy::must_use_candidate"], "deny_warnings": true }`。
+// 不配置时与直接运行 `cargo clippy` 相同：只有 clippy 默认为 deny 的 lint 会导致未通过
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ClippyPolicy {
+    // 视为错误的 lint 或 lint 组，例如 `clippy::pedantic`、`clippy::unwrap_used`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+    // 允许的 lint 或 lint 组，优先于 deny 与 deny_warnings
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+    // 所有警告都视为错误
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deny_warnings: bool,
+    // 设置时 lint 错误不再导致未通过，而是每条扣除这么多分（最多扣到 0 分）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deduct: Option<f64>,
+}
+
+impl ClippyPolicy {
+    pub fn is_default(&self) -> bool {
+        *self == ClippyPolicy::default()
+    }
+
+    // 传给 clippy 的参数（`cargo clippy -- <args>`）。allow 放在最后，使其覆盖前面的 deny
+    pub fn args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        for lint in &self.deny {
+            args.extend(["-D".to_string(), lint.clone()]);
+        }
+        if self.deny_warnings {
+            args.extend(["-D".to_string(), "warnings".to_string()]);
+        }
+        for lint in &self.allow {
+            args.extend(["-A".to_string(), lint.clone()]);
+        }
+        args
+    }
+
+    // clippy 因这些问题失败时是否改为扣分：需要设置了 deduct，并且所有错误都来自 lint（而不是编译错误）
+    pub fn deducts(&self, findings: &[Finding]) -> bool {
+        let mut errors = findings.iter().filter(|finding| finding.level == LintLevel::Error).peekable();
+        self.deduct.is_some() && errors.peek().is_some() && errors.all(|finding| finding.lint.is_some())
+    }
+
+    // 这些问题应扣除的分数，只有错误级别的问题扣分
+    pub fn deduction(&self, findings: &[Finding]) -> f64 {
+        let errors = findings.iter().filter(|finding| finding.level == LintLevel::Error).count();
+        self.deduct.map_or(0.0, |points| points * errors as f64)
+    }
+
+    // 检查配置，有问题时返回说明
+    pub fn check(&self) -> Option<String> {
+        if let Some(lint) = self.deny.iter().chain(&self.allow).find(|lint| lint.trim().is_empty()) {
+            return Some(format!("clippy: lint names must not be empty, got '{}'", lint));
+        }
+        match self.deduct {
+            Some(points) if !points.is_finite() || points <= 0.0 => {
+                Some(format!("clippy.deduct must be a positive number of points, got {}", points))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LintLevel {
+    Warning,
+    Error,
+}
+
+// clippy（以及 rustc）报告的一个问题
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Finding {
+    // lint 名称，例如 `clippy::needless_range_loop`；编译错误没有 lint 名称
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lint: Option<String>,
+    pub level: LintLevel,
+    pub message: String,
+    // 问题所在的文件（相对于项目目录）与位置
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub column: Option<u64>,
+    // 编译器渲染好的完整诊断信息，用作 clippy 失败时报告中的 stderr
+    #[serde(skip)]
+    pub rendered: String,
+}
+
+impl Finding {
+    // 形如 `src/main.rs:10:5`
+    pub fn location(&self) -> Option<String> {
+        let file = self.file.as_deref()?;
+        Some(match (self.line, self.column) {
+            (Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+            (Some(line), None) => format!("{}:{}", file, line),
+            _ => file.to_string(),
+        })
+    }
+}
+
+// 解析 `cargo clippy --message-format=json` 的输出。忽略没有位置的汇总信息（例如 `1 warning emitted`），
+// 同一位置的同一问题只保留一次
+pub fn parse(stdout: &[u8]) -> Vec<Finding> {
+    let mut findings: Vec<Finding> = Vec::new();
+    for line in String::from_utf8_lossy(stdout).lines() {
+        let Ok(message) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        if message["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &message["message"];
+        let level = match message["level"].as_str() {
+            Some("warning") => LintLevel::Warning,
+            Some("error") => LintLevel::Error,
+            _ => continue,
+        };
+        let spans = message["spans"].as_array().map(Vec::as_slice).unwrap_or_default();
+        let Some(span) = spans.iter().find(|span| span["is_primary"] == true).or(spans.first()) else {
+            continue;
+        };
+
+        let finding = Finding {
+            lint: message["code"]["code"].as_str().filter(|code| !is_error_code(code)).map(String::from),
+            level,
+            message: message["message"].as_str().unwrap_or_default().to_string(),
+            file: span["file_name"].as_str().map(String::from),
+            line: span["line_start"].as_u64(),
+            column: span["column_start"].as_u64(),
+            rendered: message["rendered"].as_str().unwrap_or_default().to_string(),
+        };
+        let duplicate = findings.iter().any(|other| {
+            (&other.lint, &other.message, &other.file, other.line, other.column)
+                == (&finding.lint, &finding.message, &finding.file, finding.line, finding.column)
+        });
+        if !duplicate {
+            findings.push(finding);
+        }
+    }
+    findings
+}
+
+// rustc 的错误代码（例如 `E0308`）表示编译错误，不是 lint
+fn is_error_code(code: &str) -> bool {
+    code.len() == 5 && code.starts_with('E') && code[1..].bytes().all(|byte| byte.is_ascii_digit())
+}
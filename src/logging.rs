@@ -0,0 +1,230 @@
+use log::{Level, LevelFilter, Record};
+use log4rs::append::console::{ConsoleAppender, Target};
+use log4rs::config::{Appender, Config, Deserialize, Deserializers, Root};
+use log4rs::encode::pattern::PatternEncoder;
+use log4rs::encode::{self, Encode};
+use log4rs::filter::{Filter, Response};
+use regex::Regex;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::LazyLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// 日志配置文件：终端输出以及 .grader-logs/ 下的滚动日志文件
+pub const CONFIG_FILE: &str = "log4rs.yaml";
+
+// 终端输出的详细程度，由 -q、-v、-vv 决定；日志文件的详细程度只由配置文件决定
+static CONSOLE_LEVEL: AtomicUsize = AtomicUsize::new(LevelFilter::Info as usize);
+
+// 终端是否显示颜色
+static COLORS: AtomicBool = AtomicBool::new(false);
+
+static ANSI_ESCAPE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\x1b\[[0-9;]*m").unwrap());
+
+// 初始化日志。没有配置文件时只输出到终端；配置文件有误时给出警告并同样只输出到终端
+pub fn init(verbosity: LevelFilter) {
+    set_console_level(verbosity);
+
+    // 输出总是带有颜色（评测输出会被缓存，不能取决于本次运行的终端），由 encoder 在写入时决定是否去掉。
+    // 设置了 NO_COLOR 或者输出不是终端（例如重定向到文件）时终端不显示颜色，日志文件始终不带颜色
+    colored::control::set_override(true);
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    COLORS.store(!no_color && io::stdout().is_terminal(), Ordering::Relaxed);
+
+    let mut deserializers = Deserializers::default();
+    deserializers.insert("verbosity", VerbosityFilterDeserializer);
+    deserializers.insert("terminal", StripColorsDeserializer { terminal: true });
+    deserializers.insert("plain", StripColorsDeserializer { terminal: false });
+
+    let mut warning = None;
+    let config = if Path::new(CONFIG_FILE).exists() {
+        // 配置中个别 appender 有误时 log4rs 会自行报告并忽略它们，一个 appender 都没有时同样退回到终端输出
+        match log4rs::config::load_config_file(CONFIG_FILE, deserializers) {
+            Ok(config) if !config.root().appenders().is_empty() => config,
+            Ok(_) => {
+                warning = Some(format!("{} has no usable appenders, logging to the terminal only", CONFIG_FILE));
+                console_config()
+            }
+            Err(e) => {
+                warning = Some(format!("Failed to load {}, logging to the terminal only: {}", CONFIG_FILE, e));
+                console_config()
+            }
+        }
+    } else {
+        console_config()
+    };
+
+    // 输出被管道截断（例如 `| head`）时不再逐条报告写入失败
+    let handler = Box::new(|e: &anyhow::Error| {
+        if e.downcast_ref::<io::Error>().is_none_or(|e| e.kind() != io::ErrorKind::BrokenPipe) {
+            eprintln!("log4rs: {}", e);
+        }
+    });
+    if let Err(e) = log4rs::config::init_config_with_err_handler(config, handler) {
+        eprintln!("Failed to initialize logging: {}", e);
+    }
+    if let Some(warning) = warning {
+        log::warn!("{}", warning);
+    }
+}
+
+// 终端不显示颜色时去掉其中的颜色代码，用于不经过日志直接写入终端的内容（watch 的界面）
+pub fn paint(text: &str) -> String {
+    if COLORS.load(Ordering::Relaxed) { text.to_string() } else { strip_colors(text) }
+}
+
+fn strip_colors(text: &str) -> String {
+    ANSI_ESCAPE.replace_all(text, "").into_owned()
+}
+
+pub fn set_console_level(level: LevelFilter) {
+    CONSOLE_LEVEL.store(level as usize, Ordering::Relaxed);
+}
+
+pub fn console_level() -> LevelFilter {
+    let level = CONSOLE_LEVEL.load(Ordering::Relaxed);
+    LevelFilter::iter().find(|filter| *filter as usize == level).unwrap_or(LevelFilter::Info)
+}
+
+// 在返回值的生命周期内关闭终端日志（日志文件不受影响），用于 watch 这样自己绘制界面的模式
+pub fn suspend_console() -> ConsoleSuspended {
+    let previous = console_level();
+    set_console_level(LevelFilter::Off);
+    ConsoleSuspended(previous)
+}
+
+pub struct ConsoleSuspended(LevelFilter);
+
+impl Drop for ConsoleSuspended {
+    fn drop(&mut self) {
+        set_console_level(self.0);
+    }
+}
+
+// 与配置文件中默认设置相同的终端输出：普通信息写入 stdout，警告与错误写入 stderr
+fn console_config() -> Config {
+    let console = |target, stream| {
+        Appender::builder()
+            .filter(Box::new(VerbosityFilter { stream }))
+            .build(stream.name(), Box::new(ConsoleAppender::builder().target(target).encoder(message_encoder()).build()))
+    };
+    Config::builder()
+        .appender(console(Target::Stdout, Stream::Stdout))
+        .appender(console(Target::Stderr, Stream::Stderr))
+        .build(Root::builder().appenders(["stdout", "stderr"]).build(LevelFilter::Trace))
+        .expect("console logging config is valid")
+}
+
+fn message_encoder() -> Box<dyn Encode> {
+    Box::new(StripColors { pattern: PatternEncoder::new("{m}{n}"), terminal: true })
+}
+
+// 终端 appender 输出哪些级别：stdout 输出 info 及以下，stderr 输出警告与错误
+#[derive(serde::Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Stream {
+    Stdout,
+    Stderr,
+}
+
+impl Stream {
+    fn name(&self) -> &'static str {
+        match self {
+            Stream::Stdout => "stdout",
+            Stream::Stderr => "stderr",
+        }
+    }
+}
+
+// 按命令行指定的详细程度过滤终端输出
+#[derive(Debug)]
+struct VerbosityFilter {
+    stream: Stream,
+}
+
+impl Filter for VerbosityFilter {
+    fn filter(&self, record: &Record) -> Response {
+        let to_stderr = record.level() <= Level::Warn;
+        if record.level() > console_level() || to_stderr != (self.stream == Stream::Stderr) {
+            Response::Reject
+        } else {
+            Response::Neutral
+        }
+    }
+}
+
+// 配置文件中的 `kind: verbosity` 过滤器
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct VerbosityFilterConfig {
+    stream: Stream,
+}
+
+struct VerbosityFilterDeserializer;
+
+impl Deserialize for VerbosityFilterDeserializer {
+    type Trait = dyn Filter;
+    type Config = VerbosityFilterConfig;
+
+    fn deserialize(&self, config: VerbosityFilterConfig, _: &Deserializers) -> anyhow::Result<Box<dyn Filter>> {
+        Ok(Box::new(VerbosityFilter { stream: config.stream }))
+    }
+}
+
+// 去掉颜色代码的 pattern encoder。terminal 为 true 时终端支持颜色则保留颜色（用于终端），否则总是去掉（用于日志文件）
+#[derive(Debug)]
+struct StripColors {
+    pattern: PatternEncoder,
+    terminal: bool,
+}
+
+impl Encode for StripColors {
+    fn encode(&self, w: &mut dyn encode::Write, record: &Record) -> anyhow::Result<()> {
+        if self.terminal && COLORS.load(Ordering::Relaxed) {
+            return self.pattern.encode(w, record);
+        }
+
+        let mut buffer = Buffer(Vec::new());
+        self.pattern.encode(&mut buffer, record)?;
+        w.write_all(strip_colors(&String::from_utf8_lossy(&buffer.0)).as_bytes())?;
+        Ok(())
+    }
+}
+
+struct Buffer(Vec<u8>);
+
+impl io::Write for Buffer {
+    fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+        self.0.write(bytes)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl encode::Write for Buffer {}
+
+// 配置文件中的 `kind: terminal` 与 `kind: plain` 编码器，pattern 的写法与 `kind: pattern` 相同
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StripColorsConfig {
+    pattern: Option<String>,
+}
+
+struct StripColorsDeserializer {
+    terminal: bool,
+}
+
+impl Deserialize for StripColorsDeserializer {
+    type Trait = dyn Encode;
+    type Config = StripColorsConfig;
+
+    fn deserialize(&self, config: StripColorsConfig, _: &Deserializers) -> anyhow::Result<Box<dyn Encode>> {
+        let pattern = match config.pattern {
+            Some(pattern) => PatternEncoder::new(&pattern),
+            None => PatternEncoder::default(),
+        };
+        Ok(Box::new(StripColors { pattern, terminal: self.terminal }))
+    }
+}
@@ -1,31 +1,204 @@
+use colored::Colorize;
+use log::{Level, debug, error, info, warn};
 use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
 use std::fs::{self, File};
+use std::io::{self, Write as _};
 use std::path::{Path, PathBuf};
-use std::process::{Command, exit};
-use std::time::Instant;
-use std::io::{self, Write};
+use std::process::exit;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{OnceLock, mpsc};
+use std::thread;
+use std::time::{Duration, Instant};
+
+mod cache;
+mod catalogue;
+mod cli;
+mod diagnostics;
+mod diff;
+mod evaluators;
+mod files;
+mod formats;
+mod hidden;
+mod hints;
+mod history;
+mod integrity;
+mod libtest;
+mod lints;
+mod logging;
+mod process;
+mod progress;
+mod reset;
+mod sandbox;
+mod scratch;
+mod scoring;
+mod selection;
+mod source;
+mod toolchain;
+mod validation;
+mod watch;
+
+use cache::Cache;
+use cli::{Command, EXIT_BELOW_THRESHOLD, EXIT_ENVIRONMENT, EXIT_USAGE, Options, Usage};
+use diagnostics::{Diagnostics, Stage};
+use evaluators::{Context, ExerciseKind};
+use formats::Format;
+use hints::Hint;
+use history::{Attempt, HISTORY_FILE, Run};
+use integrity::Hashes;
+use libtest::{TestCase, TestStatus, TestSummary};
+use lints::{ClippyPolicy, Finding, LintLevel};
+use progress::DifficultyProgress;
+use reset::BACKUP_DIR;
+use sandbox::Limits;
+use scratch::Scratch;
+use scoring::ScoringPolicy;
+use selection::{Difficulty, Selection};
+use toolchain::Toolchain;
+use validation::Severity;
+
+// 未在配置文件中指定 timeout 时，每道题允许的最长评测时间（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
+// 评测结果缓存目录
+const CACHE_DIR: &str = ".grader-cache";
+
+// history 命令显示的最近评测次数
+const HISTORY_TREND_RUNS: usize = 20;
+
+// 默认的配置文件路径
+const CONFIG_FILE: &str = "exercise_config.json";
+
+// 默认的题目目录
+const DEFAULT_EXERCISES_DIR: &str = "./exercises";
+
+// 本次运行使用的题目目录，启动时根据 --exercises-dir 设置
+static EXERCISES_DIR: OnceLock<String> = OnceLock::new();
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 struct Exercise {
     name: String,
     path: String,
-    #[serde(rename = "type")]
-    exercise_type: String,  
-    score: i32, 
+    // `type` 字段以及该类型特有的字段；各类型拒绝未知的字段，拼写错误会直接报错而不是被忽略
+    #[serde(flatten)]
+    kind: ExerciseKind,
+    score: i32,
+    // 覆盖默认超时时间（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timeout: Option<u64>,
+    // 计分方式，默认全部通过才得分
+    #[serde(default, skip_serializing_if = "ScoringPolicy::is_all_or_nothing")]
+    scoring: ScoringPolicy,
+    // 覆盖运行测试程序时的默认资源限制
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    limits: Option<Limits>,
+    // clippy 规则：拒绝与允许的 lint、警告是否导致未通过、是否改为扣分
+    #[serde(default, skip_serializing_if = "ClippyPolicy::is_default")]
+    clippy: ClippyPolicy,
+    // 题目提示，可以是一条或按失败次数逐条解锁的多条
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hint: Option<Hint>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
 struct ExerciseConfig {
     easy: Vec<Exercise>,
     normal: Vec<Exercise>,
     hard: Vec<Exercise>,
 }
 
+impl Exercise {
+    // 题目源文件（或 Cargo 项目目录）的路径
+    fn source_path(&self) -> PathBuf {
+        exercise_file(&self.path)
+    }
+}
+
+// exercises/ 目录下的文件路径
+fn exercise_file(path: &str) -> PathBuf {
+    let dir = EXERCISES_DIR.get().map_or(DEFAULT_EXERCISES_DIR, String::as_str);
+    PathBuf::from(format!("{}/{}", dir, path))
+}
+
+impl ExerciseConfig {
+    // 按 easy、normal、hard 的顺序遍历所有题目
+    fn exercises(&self) -> impl Iterator<Item = (Difficulty, &Exercise)> {
+        let easy = self.easy.iter().map(|exercise| (Difficulty::Easy, exercise));
+        let normal = self.normal.iter().map(|exercise| (Difficulty::Normal, exercise));
+        let hard = self.hard.iter().map(|exercise| (Difficulty::Hard, exercise));
+        easy.chain(normal).chain(hard)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 struct ExerciseResult {
     name: String,
     result: bool,
-    score: i32, 
+    outcome: Outcome,
+    score: f64,
+    // 评测耗时（毫秒）
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    diagnostics: Option<Diagnostics>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    tests: Vec<TestCase>,
+    // clippy 发现的问题
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    lints: Vec<Finding>,
+    // 是否使用隐藏测试代替了题目自带的测试
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    hidden_tests: bool,
+}
+
+// 单道题的评测结果
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Passed,
+    Failed,
+    TimedOut,
+    // 测试程序超出了沙箱的资源限制
+    SandboxViolation,
+    // 源文件中还有 `// I AM NOT DONE` 标记，不计分
+    InProgress,
+    // 评测环境有问题（例如没有安装 clippy、无法运行 cargo），不是题目本身未通过
+    EnvironmentError,
+}
+
+impl Outcome {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Outcome::Passed => "passed",
+            Outcome::Failed => "failed",
+            Outcome::TimedOut => "timed_out",
+            Outcome::SandboxViolation => "sandbox_violation",
+            Outcome::InProgress => "in_progress",
+            Outcome::EnvironmentError => "environment_error",
+        }
+    }
+}
+
+// 评测一道题得到的结论
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct Evaluation {
+    outcome: Outcome,
+    duration_ms: u64,
+    diagnostics: Option<Diagnostics>,
+    // 测试阶段中各个测试的结果
+    #[serde(default)]
+    tests: Vec<TestCase>,
+    // clippy 发现的问题
+    #[serde(default)]
+    lints: Vec<Finding>,
+}
+
+// 某个评测阶段未通过（失败或超时）。诊断信息较大，放在堆上使 Result 的 Err 保持较小
+struct StageFailure {
+    outcome: Outcome,
+    diagnostics: Box<Diagnostics>,
+    tests: Vec<TestCase>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -33,226 +206,1079 @@ struct Statistics {
     total_exercises: usize,
     total_successes: usize,
     total_failures: usize,
-    total_score: i32,  
+    total_timeouts: usize,
+    #[serde(default)]
+    total_violations: usize,
+    #[serde(default)]
+    total_in_progress: usize,
+    #[serde(default)]
+    total_environment_errors: usize,
+    total_score: f64,
     total_time: u64,
+    // 所有题目中各个测试的结果统计
+    #[serde(default)]
+    tests: TestSummary,
+    // 各难度已完成、进行中与未通过的题目数
+    #[serde(default)]
+    progress: Vec<DifficultyProgress>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct Report {
+    // 只评测了部分题目时记录筛选条件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    selection: Option<Selection>,
+    // 使用的隐藏测试目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hidden_tests: Option<String>,
+    // 评测使用的 rustc、cargo 与 clippy 版本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    toolchain: Option<Toolchain>,
     exercises: Vec<ExerciseResult>,
     statistics: Statistics,
 }
 
+// 一次评测运行中共享的选项、结果缓存与临时构建目录
+struct Grader {
+    options: Options,
+    cache: Option<Cache>,
+    scratch: Scratch,
+    // exercise_hashes.json 中记录的受保护区域，文件不存在时不检查
+    hashes: Option<Hashes>,
+}
+
+impl Grader {
+    // 评测一道题，源文件与配置都没有变化时复用缓存中的结果
+    fn evaluate(&self, exercise: &Exercise) -> (Evaluation, ExerciseOutput) {
+        self.evaluate_with_cache(exercise, true)
+    }
+
+    // 忽略缓存重新评测一道题，并用新的结果更新缓存
+    fn reevaluate(&self, exercise: &Exercise) -> (Evaluation, ExerciseOutput) {
+        self.evaluate_with_cache(exercise, false)
+    }
+
+    fn evaluate_with_cache(&self, exercise: &Exercise, use_cached: bool) -> (Evaluation, ExerciseOutput) {
+        if let Some(rejected) = self.check_protected_regions(exercise) {
+            return rejected;
+        }
+
+        let timeout = exercise.timeout.unwrap_or(self.options.timeout);
+        let hidden = self.options.hidden_suite(exercise);
+        let cache = self
+            .cache
+            .as_ref()
+            .and_then(|cache| Some((cache, cache.key(exercise, timeout, self.options.sandbox, hidden.as_deref())?)));
+
+        if let (true, Some((cache, key))) = (use_cached, &cache) {
+            if let Some((evaluation, mut output)) = cache.load(exercise, key) {
+                output.info("(cached result, run with --no-cache to re-evaluate)".to_string());
+                return (evaluation, output);
+            }
+        }
+
+        let (evaluation, mut output) = evaluate_exercise(exercise, &self.options, &self.scratch);
+
+        // 超时可能只是机器繁忙导致的，环境问题修复后结果也会不同，都不写入缓存
+        if let Some((cache, key)) = cache {
+            if !matches!(evaluation.outcome, Outcome::TimedOut | Outcome::EnvironmentError) {
+                if let Err(e) = cache.store(exercise, key, &evaluation, &output) {
+                    output.warn(format!("Failed to update grading cache: {}", e));
+                }
+            }
+        }
+
+        (evaluation, output)
+    }
+
+    // 受保护的区域被修改时不评测，直接记为未通过。使用隐藏测试时学生的测试会被替换，不检查测试代码
+    fn check_protected_regions(&self, exercise: &Exercise) -> Option<(Evaluation, ExerciseOutput)> {
+        // 无法读取题目文件时交给评测过程报告错误
+        let mut modified = integrity::modified_regions(self.hashes.as_ref()?, exercise).ok()?;
+        if self.options.hidden_suite(exercise).is_some() {
+            modified.retain(|region| !integrity::is_test_region(region));
+        }
+        if modified.is_empty() {
+            return None;
+        }
+
+        let mut output = ExerciseOutput::default();
+        output.info(format!("\nEvaluating {}: {}", exercise.kind.name(), exercise.name));
+        output.info(format!("{}: PROTECTED REGION MODIFIED", exercise.source_path().display()).red().to_string());
+        for region in &modified {
+            output.info(format!("  modified: {}", region).red().to_string());
+        }
+
+        let evaluation = Evaluation {
+            outcome: Outcome::Failed,
+            duration_ms: 0,
+            diagnostics: Some(Diagnostics {
+                stage: Some(Stage::Integrity),
+                stderr: format!("protected region modified: {}", modified.join(", ")),
+                ..Diagnostics::default()
+            }),
+            tests: Vec::new(),
+            lints: Vec::new(),
+        };
+        Some((evaluation, output))
+    }
+}
+
+// 单个习题评测时产生的输出，评测结束后统一写入日志，保证并行评测时各习题的输出不会交错
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct ExerciseOutput {
+    lines: Vec<(Level, String)>,
+}
+
+impl ExerciseOutput {
+    fn info(&mut self, line: String) {
+        self.lines.push((Level::Info, line));
+    }
+
+    fn warn(&mut self, line: String) {
+        self.lines.push((Level::Warn, line));
+    }
+
+    // 只在 -v 时显示，总是写入日志文件
+    fn debug(&mut self, line: String) {
+        self.lines.push((Level::Debug, line));
+    }
+
+    // 只在 -vv 时显示
+    fn trace(&mut self, line: String) {
+        self.lines.push((Level::Trace, line));
+    }
+
+    fn print(&self) {
+        for (level, line) in &self.lines {
+            log::log!(*level, "{}", line);
+        }
+    }
+}
+
 fn main() {
     let args: Vec<String> = std::env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Please provide a command: 'watch' or 'all'");
-        exit(1);
-    }
+    let options = match cli::parse(&args) {
+        Ok(options) => options,
+        Err(Usage::Help(help)) => {
+            print!("{}", help);
+            return;
+        }
+        Err(Usage::Error(message)) => {
+            eprintln!("{}\nRun 'cargotest --help' for usage", message);
+            exit(EXIT_USAGE);
+        }
+    };
+    EXERCISES_DIR.set(options.exercises_dir.clone()).ok();
+    logging::init(options.verbosity);
 
-    let mode = &args[1];
     let start_time = Instant::now();
 
-    
-    let config = match load_exercise_config("exercise_config.json") {
+    // 汇总已有的报告，不需要配置文件
+    if options.command == Command::Report {
+        exit(summarize_report(&options));
+    }
+
+    let config_file = options.config.display().to_string();
+    let config = match load_exercise_config(&options.config) {
         Ok(cfg) => cfg,
         Err(e) => {
-            eprintln!("Failed to load config file: {}", e);
+            error!("Failed to load config file {}: {}", config_file, e);
             exit(1);
         }
     };
 
+    match options.command {
+        Command::CheckConfig => exit(check_config(&config, &config_file)),
+        Command::History => exit(show_history(&config)),
+        // 题目文件可能已经损坏或被删除，重置与检查前不检查配置中的路径
+        Command::Reset | Command::Verify => match options.selection.select(&config) {
+            Ok(exercises) if options.command == Command::Reset => exit(reset_exercises(&exercises, &options)),
+            Ok(exercises) => exit(verify(&config, &config_file, &integrity::path(&options.config), &exercises)),
+            Err(e) => {
+                error!("{}", e);
+                exit(1);
+            }
+        },
+        _ => {}
+    }
+
+    // 配置有错误时不开始评测，警告只提示
+    let problems = validation::validate(&config);
+    for problem in &problems {
+        match problem.severity {
+            Severity::Error => error!("{}", problem),
+            Severity::Warning => warn!("{}", problem),
+        }
+    }
+    if problems.iter().any(|problem| problem.severity == Severity::Error) {
+        error!("{} is invalid, fix the errors above first", config_file);
+        exit(1);
+    }
+
+    if options.command == Command::UpdateHashes {
+        exit(update_hashes(&config, &options));
+    }
+
+    let hashes_file = integrity::path(&options.config);
+    let hashes = match integrity::load(&hashes_file) {
+        Ok(hashes) => hashes,
+        Err(e) => {
+            error!("Failed to load {}: {}", hashes_file.display(), e);
+            exit(1);
+        }
+    };
+
+    let exercises = match options.selection.select(&config) {
+        Ok(exercises) => exercises,
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    };
+
+    match options.command {
+        Command::Hint => {
+            show_hints(&exercises);
+            return;
+        }
+        Command::List => {
+            exit(list_exercises(&config, &exercises, &options));
+        }
+        _ => {}
+    }
+
+    // 工具链有问题时不开始评测，以免把环境问题记为题目未通过
+    let Some(toolchain) = preflight(&exercises) else {
+        exit(EXIT_ENVIRONMENT);
+    };
+    debug!("Evaluating {} exercise(s) with {} job(s)", exercises.len(), options.jobs);
+
     let mut report = Report {
+        selection: if options.selection.is_empty() { None } else { Some(options.selection.clone()) },
+        hidden_tests: options.hidden_tests.as_ref().map(|dir| dir.display().to_string()),
+        toolchain: Some(toolchain.clone()),
         exercises: Vec::new(),
         statistics: Statistics {
             total_exercises: 0,
             total_successes: 0,
             total_failures: 0,
-            total_score: 0,
+            total_timeouts: 0,
+            total_violations: 0,
+            total_in_progress: 0,
+            total_environment_errors: 0,
+            total_score: 0.0,
             total_time: 0,
+            tests: TestSummary::default(),
+            progress: Vec::new(),
         },
     };
 
-    
-    evaluate_exercises_from_config(mode, config, &mut report);
 
-    
+    let cache = if options.no_cache {
+        None
+    } else {
+        match Cache::open(Path::new(CACHE_DIR), &toolchain) {
+            Ok(cache) => Some(cache),
+            Err(e) => {
+                warn!("Failed to open grading cache {}: {}", CACHE_DIR, e);
+                None
+            }
+        }
+    };
+    let scratch = match Scratch::new() {
+        Ok(scratch) => scratch,
+        Err(e) => {
+            error!("Failed to create build directory: {}", e);
+            exit(1);
+        }
+    };
+    if let Err(e) = scratch.clean_up_on_interrupt() {
+        warn!("Failed to install Ctrl-C handler: {}", e);
+    }
+    let grader = Grader { options, cache, scratch, hashes };
+
+    if let Err(e) = evaluate_exercises(&grader, exercises, &mut report) {
+        error!("{}", e);
+        // exit 不会运行析构函数，先删除临时构建目录
+        drop(grader);
+        exit(1);
+    }
+
+
     report.statistics.total_time = start_time.elapsed().as_secs();
-    report.statistics.total_exercises = report.statistics.total_successes + report.statistics.total_failures;
+    report.statistics.total_exercises = report.statistics.total_successes
+        + report.statistics.total_failures
+        + report.statistics.total_in_progress
+        + report.statistics.total_environment_errors;
+    report.statistics.progress = progress::by_difficulty(&config, &report.exercises);
+
 
-    
-    println!("\nSummary:");
-    println!("Total exercises: {}", report.statistics.total_exercises);
-    println!("Total successes: {}", report.statistics.total_successes);
-    println!("Total failures: {}", report.statistics.total_failures);
-    println!("Total score: {}", report.statistics.total_score);
+    print_summary(&report.statistics);
 
-    
-    if let Err(e) = save_report_to_json("report.json", &report) {
-        eprintln!("Error saving report: {}", e);
+
+    let output = grader.options.output.as_deref().unwrap_or(grader.options.format.default_output());
+    if let Err(e) = save_report(output, grader.options.format, &report) {
+        error!("Error saving report: {}", e);
+    }
+
+    // 环境问题与题目无关，不计入历史（否则会影响提示的解锁与退步的统计）
+    let run = Run::now(
+        report
+            .exercises
+            .iter()
+            .filter(|exercise| exercise.outcome != Outcome::EnvironmentError)
+            .map(|exercise| Attempt { name: exercise.name.clone(), outcome: exercise.outcome, score: exercise.score })
+            .collect(),
+    );
+    // 所有题目都因环境问题没能评测时不记录
+    if !run.exercises.is_empty() {
+        if let Err(e) = history::append(HISTORY_FILE, &run) {
+            warn!("Failed to update grading history {}: {}", HISTORY_FILE, e);
+        }
+    }
+
+    let mut status = check_threshold(&grader.options, report.statistics.total_score);
+    if report.statistics.total_environment_errors > 0 {
+        error!(
+            "{} exercise(s) could not be graded because of problems with the environment, fix them and grade again",
+            report.statistics.total_environment_errors
+        );
+        status = EXIT_ENVIRONMENT;
+    }
+    if status != 0 {
+        drop(grader);
+        exit(status);
+    }
+}
+
+fn print_summary(statistics: &Statistics) {
+    info!("\nSummary:");
+    info!("Total exercises: {}", statistics.total_exercises);
+    info!("Total successes: {}", statistics.total_successes);
+    info!("Total failures: {}", statistics.total_failures);
+    info!("Total timeouts: {}", statistics.total_timeouts);
+    info!("Total sandbox violations: {}", statistics.total_violations);
+    info!("Total in progress: {}", statistics.total_in_progress);
+    info!("Total environment errors: {}", statistics.total_environment_errors);
+    info!("Total score: {}", statistics.total_score);
+    let tests = &statistics.tests;
+    info!("Total tests: {} ({} passed, {} failed, {} ignored)", tests.total(), tests.passed, tests.failed, tests.ignored);
+    for progress in &statistics.progress {
+        info!(
+            "  {}: {} done, {} in progress, {} failing",
+            progress.difficulty, progress.done, progress.in_progress, progress.failing
+        );
     }
 }
 
+// 指定了 --min-score 时检查总分，低于要求时返回 EXIT_BELOW_THRESHOLD
+fn check_threshold(options: &Options, score: f64) -> i32 {
+    match options.min_score {
+        Some(min_score) if score < min_score => {
+            error!("Total score {} is below the required {}", score, min_score);
+            EXIT_BELOW_THRESHOLD
+        }
+        Some(min_score) => {
+            info!("{}", format!("Total score {} meets the required {}", score, min_score).green());
+            0
+        }
+        None => 0,
+    }
+}
 
-fn load_exercise_config(file_path: &str) -> Result<ExerciseConfig, io::Error> {
+// report：输出已保存的 JSON 报告的汇总，指定了 --format 或 --output 时同时把报告转换为对应的格式
+fn summarize_report(options: &Options) -> i32 {
+    let path = options.report.clone().unwrap_or_else(|| PathBuf::from(Format::Json.default_output()));
+    let report: Report = match fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+    {
+        Ok(report) => report,
+        Err(e) => {
+            error!("Failed to read report {}: {}", path.display(), e);
+            return 1;
+        }
+    };
+
+    print_summary(&report.statistics);
+
+    if options.format != Format::Json || options.output.is_some() {
+        let output = options.output.as_deref().unwrap_or(options.format.default_output());
+        if let Err(e) = save_report(output, options.format, &report) {
+            error!("Error saving report: {}", e);
+            return 1;
+        }
+        info!("Report written to {}", output);
+    }
+
+    check_threshold(options, report.statistics.total_score)
+}
+
+// 评测前检查工具链：按 rust-toolchain 文件固定工具链，并确认 rustc、cargo 以及题目需要的 clippy 可用。
+// 有问题时输出错误并返回 None
+fn preflight(exercises: &[Exercise]) -> Option<Toolchain> {
+    let pinned = match toolchain::pinned() {
+        Ok(pinned) => pinned,
+        Err(e) => {
+            error!("{}", e);
+            return None;
+        }
+    };
+    // 部分命令在系统临时目录中运行（例如隐藏测试的副本），rustup 在那里找不到 rust-toolchain 文件，
+    // 通过环境变量让所有子进程都使用固定的工具链；此时还没有启动其他线程
+    if let Some(pinned) = &pinned {
+        std::env::set_var("RUSTUP_TOOLCHAIN", pinned);
+    }
+
+    let toolchain = match toolchain::detect(pinned) {
+        Ok(toolchain) => toolchain,
+        Err(e) => {
+            error!("{}", e);
+            return None;
+        }
+    };
+    let needs_clippy = exercises.iter().any(|exercise| exercise.kind.evaluator().uses_clippy());
+    let problems = toolchain.problems(needs_clippy);
+    for problem in &problems {
+        error!("{}", problem);
+    }
+    if !problems.is_empty() {
+        error!("The Rust toolchain cannot grade these exercises, fix the problems above first");
+        return None;
+    }
+
+    debug!(
+        "Toolchain: {} ({}), {}, {}",
+        toolchain.rustc,
+        toolchain.host,
+        toolchain.cargo,
+        toolchain.clippy.as_deref().unwrap_or("clippy not installed")
+    );
+    Some(toolchain)
+}
+
+// verify：检查配置文件、工具链，以及题目中受保护的区域（测试、函数签名）是否被修改，不评测题目
+fn verify(config: &ExerciseConfig, config_file: &str, hashes_file: &Path, exercises: &[Exercise]) -> i32 {
+    let mut status = check_config(config, config_file);
+    match preflight(exercises) {
+        Some(toolchain) => info!("{}", format!("Toolchain {} can grade the exercises", toolchain.rustc).green()),
+        None if status == 0 => status = EXIT_ENVIRONMENT,
+        None => {}
+    }
+
+    let hashes = match integrity::load(hashes_file) {
+        Ok(Some(hashes)) => hashes,
+        Ok(None) => {
+            warn!("{} not found, protected regions were not checked", hashes_file.display());
+            return status;
+        }
+        Err(e) => {
+            error!("Failed to load {}: {}", hashes_file.display(), e);
+            return 1;
+        }
+    };
+
+    let mut modified = 0;
+    for exercise in exercises {
+        match integrity::modified_regions(&hashes, exercise) {
+            Ok(regions) if regions.is_empty() => {}
+            Ok(regions) => {
+                error!("{}: protected region modified: {}", exercise.name, regions.join(", "));
+                modified += 1;
+            }
+            Err(e) => {
+                error!("Failed to read {}: {}", exercise.source_path().display(), e);
+                modified += 1;
+            }
+        }
+    }
+
+    if modified == 0 {
+        info!("{}", format!("Protected regions of {} exercises are unmodified", exercises.len()).green());
+    } else {
+        status = 1;
+    }
+    status
+}
+
+// list：列出选中的题目以及最近一次评测的结果，可以按状态筛选、排序，或以 JSON 输出供脚本使用
+fn list_exercises(config: &ExerciseConfig, exercises: &[Exercise], options: &Options) -> i32 {
+    let runs = match history::load(HISTORY_FILE) {
+        Ok(runs) => runs,
+        Err(e) => {
+            error!("Failed to read grading history {}: {}", HISTORY_FILE, e);
+            return 1;
+        }
+    };
+    let report = PathBuf::from(Format::Json.default_output());
+    let mut entries = catalogue::entries(config, exercises, &runs, Some(&report));
+    if !options.statuses.is_empty() {
+        entries.retain(|entry| options.statuses.contains(&entry.status()));
+    }
+    match options.sort {
+        Some(key) => catalogue::sort(&mut entries, key, options.reverse),
+        None if options.reverse => entries.reverse(),
+        None => {}
+    }
+
+    // JSON 直接写入 stdout，不受 -q 影响，也不写入日志文件
+    if options.json {
+        return match serde_json::to_string_pretty(&entries) {
+            Ok(json) => {
+                println!("{}", json);
+                0
+            }
+            Err(e) => {
+                error!("Failed to serialize the exercise list: {}", e);
+                1
+            }
+        };
+    }
+
+    if entries.is_empty() {
+        info!("No exercises match the given filters");
+        return 0;
+    }
+
+    let rows: Vec<[String; 7]> = entries
+        .iter()
+        .map(|entry| {
+            let result = match (entry.last_result, entry.last_score) {
+                (Some(outcome), Some(score)) if score > 0.0 && outcome != Outcome::Passed => {
+                    format!("{} ({})", outcome.as_str(), score)
+                }
+                (Some(outcome), _) => outcome.as_str().to_string(),
+                (None, _) => "-".to_string(),
+            };
+            [
+                entry.name.to_string(),
+                entry.difficulty.to_string(),
+                entry.kind.to_string(),
+                entry.points.to_string(),
+                result,
+                entry.last_run.map_or("-".to_string(), history::format_timestamp),
+                if entry.done { "✓".to_string() } else { String::new() },
+            ]
+        })
+        .collect();
+    let header = ["Name", "Difficulty", "Type", "Points", "Last result", "Last run", "Done"];
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: [String; 7]| {
+        let [name, difficulty, kind, points, result, last_run, done] = cells;
+        format!(
+            "{:<w0$}  {:<w1$}  {:<w2$}  {:>w3$}  {}  {:<w5$}  {}",
+            name,
+            difficulty,
+            kind,
+            points,
+            result,
+            last_run,
+            done,
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+            w3 = widths[3],
+            w5 = widths[5],
+        )
+    };
+    info!("{}", line(header.map(str::to_string)).bold());
+    for (entry, mut row) in entries.iter().zip(rows) {
+        // 先补齐宽度再上色，颜色代码不计入列宽
+        let result = format!("{:<width$}", row[4], width = widths[4]);
+        row[4] = match entry.last_result {
+            Some(Outcome::Passed) => result.green().to_string(),
+            Some(Outcome::InProgress) => result.yellow().to_string(),
+            Some(_) => result.red().to_string(),
+            None => result,
+        };
+        info!("{}", line(row));
+    }
+
+    let passed = entries.iter().filter(|entry| entry.last_result == Some(Outcome::Passed)).count();
+    let points: i32 = entries.iter().map(|entry| entry.points).sum();
+    let score = entries.iter().filter_map(|entry| entry.last_score).fold(0.0, |total, score| total + score);
+    info!("{} exercises, {} passed in their last run, {}/{} points", entries.len(), passed, score, points);
+    0
+}
+
+// reset：确认后备份题目的当前版本并用模板覆盖，有题目未能重置时返回非零退出码
+fn reset_exercises(exercises: &[Exercise], options: &Options) -> i32 {
+    let mut failed = 0;
+
+    for exercise in exercises {
+        let Some(template) = reset::template(&options.templates, exercise) else {
+            error!("No template for {} in {}", exercise.name, options.templates.display());
+            failed += 1;
+            continue;
+        };
+
+        if !options.yes && !confirm(&format!("Reset {} to its template? Your current version will be backed up.", exercise.name)) {
+            info!("Skipped {}", exercise.name);
+            continue;
+        }
+
+        match reset::reset(exercise, &template, Path::new(BACKUP_DIR)) {
+            Ok(Some(backup)) => {
+                info!("{}, previous version saved to {}", format!("Reset {}", exercise.name).green(), backup.display())
+            }
+            Ok(None) => info!("{}", format!("Reset {}", exercise.name).green()),
+            Err(e) => {
+                error!("Failed to reset {}: {}", exercise.name, e);
+                failed += 1;
+            }
+        }
+    }
+
+    if failed == 0 { 0 } else { 1 }
+}
+
+// 询问用户是否继续，只有输入 y 或 yes 时返回 true
+fn confirm(question: &str) -> bool {
+    print!("{} [y/N] ", question);
+    io::stdout().flush().ok();
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+// history：列出最近几次评测的分数变化、退步的题目以及每道题第一次通过前的尝试次数与用时
+fn show_history(config: &ExerciseConfig) -> i32 {
+    let runs = match history::load(HISTORY_FILE) {
+        Ok(runs) => runs,
+        Err(e) => {
+            error!("Failed to read grading history {}: {}", HISTORY_FILE, e);
+            return 1;
+        }
+    };
+    if runs.is_empty() {
+        info!("No grading history yet, run 'all' or 'watch' first");
+        return 0;
+    }
+
+    let points = |name: &str| {
+        config.exercises().find(|(_, exercise)| exercise.name == name).map_or(0, |(_, exercise)| exercise.score)
+    };
+
+    let shown = runs.len().min(HISTORY_TREND_RUNS);
+    info!("{} (last {} of {} runs):", "Score trend".bold(), shown, runs.len());
+    for (index, run) in runs.iter().enumerate().skip(runs.len() - shown) {
+        let total: i32 = run.exercises.iter().map(|attempt| points(&attempt.name)).sum();
+        let filled = if total > 0 { (run.score() / total as f64 * 20.0).round() as usize } else { 0 };
+        // 与之前最近一次评测了同样题目的运行比较
+        let delta = match runs[..index].iter().rev().find(|previous| previous.same_exercises(run)) {
+            Some(previous) if previous.score() != run.score() => {
+                let delta = ((run.score() - previous.score()) * 100.0).round() / 100.0;
+                let text = format!("{:+}", delta);
+                format!("  {}", if delta > 0.0 { text.green() } else { text.red() })
+            }
+            _ => String::new(),
+        };
+        info!(
+            "  {}  {:<9} {}{}  {}/{} ({}/{} passed){}",
+            history::format_timestamp(run.timestamp),
+            run.commit.as_deref().unwrap_or("-"),
+            "█".repeat(filled.min(20)),
+            "░".repeat(20 - filled.min(20)),
+            run.score(),
+            total,
+            run.passed(),
+            run.exercises.len(),
+            delta
+        );
+    }
+
+    info!("\n{}:", "Regressions".bold());
+    let regressions = history::regressions(&runs);
+    if regressions.is_empty() {
+        info!("  none");
+    }
+    for regression in &regressions {
+        info!(
+            "  {}: passed -> {} at {} ({})",
+            regression.name.red(),
+            regression.outcome.as_str(),
+            history::format_timestamp(regression.run.timestamp),
+            regression.run.commit.as_deref().unwrap_or("no commit")
+        );
+    }
+
+    info!("\n{}:", "Time to first pass".bold());
+    let passes = history::first_passes(&runs);
+    let width = passes.iter().map(|pass| pass.name.len()).max().unwrap_or(0);
+    for pass in &passes {
+        let attempts = format!("{} attempt{}", pass.attempts, if pass.attempts == 1 { "" } else { "s" });
+        match pass.elapsed() {
+            Some(elapsed) => info!(
+                "  {:<width$}  {:<12} {}",
+                pass.name,
+                history::format_duration(elapsed).green(),
+                attempts,
+                width = width
+            ),
+            None => info!("  {:<width$}  {:<12} {}", pass.name, "not passed".yellow(), attempts, width = width),
+        }
+    }
+    0
+}
+
+// hint：输出题目当前已解锁的提示，未通过的次数取自本地评测历史
+fn show_hints(exercises: &[Exercise]) {
+    let runs = history::load(HISTORY_FILE).unwrap_or_else(|e| {
+        warn!("Failed to read grading history {}: {}", HISTORY_FILE, e);
+        Vec::new()
+    });
+
+    for exercise in exercises {
+        info!("{}", exercise.name.bold());
+        for line in hints::lines(exercise, history::failures_since_pass(&runs, &exercise.name)) {
+            info!("{}", line);
+        }
+        info!("");
+    }
+}
+
+// update-hashes：根据题目模板重新记录受保护区域的哈希，题目模板修改后由维护者运行。
+// 没有模板目录时使用题目目录中的文件，此时这些文件应当是未作答的版本
+fn update_hashes(config: &ExerciseConfig, options: &Options) -> i32 {
+    let root = if options.templates.is_dir() {
+        options.templates.clone()
+    } else {
+        warn!(
+            "Template directory {} not found, recording the regions of the files in {} instead",
+            options.templates.display(),
+            options.exercises_dir
+        );
+        PathBuf::from(&options.exercises_dir)
+    };
+    let hashes = match integrity::record(config, &root) {
+        Ok(hashes) => hashes,
+        Err(e) => {
+            error!("Failed to read exercises: {}", e);
+            return 1;
+        }
+    };
+    let hashes_file = integrity::path(&options.config);
+    if let Err(e) = integrity::save(&hashes_file, &hashes) {
+        error!("Failed to write {}: {}", hashes_file.display(), e);
+        return 1;
+    }
+
+    let regions: usize = hashes.values().map(|regions| regions.len()).sum();
+    info!(
+        "Recorded {} protected regions of {} exercises in {} from {}",
+        regions,
+        hashes.len(),
+        hashes_file.display(),
+        root.display()
+    );
+    0
+}
+
+// check-config：检查配置文件并输出所有问题，有错误时返回非零退出码
+fn check_config(config: &ExerciseConfig, config_file: &str) -> i32 {
+    let problems = validation::validate(config);
+    for problem in &problems {
+        info!("{}", problem);
+    }
+
+    let errors = problems.iter().filter(|problem| problem.severity == Severity::Error).count();
+    let warnings = problems.len() - errors;
+    let exercises = config.exercises().count();
+    let points: i32 = config.exercises().map(|(_, exercise)| exercise.score).sum();
+
+    if errors == 0 {
+        info!(
+            "{}: {} exercises, {} points, {} warning(s)",
+            format!("{} is valid", config_file).green(), exercises, points, warnings
+        );
+        0
+    } else {
+        info!("{}", format!("{} has {} error(s) and {} warning(s)", config_file, errors, warnings).red());
+        1
+    }
+}
+
+fn load_exercise_config(file_path: &Path) -> Result<ExerciseConfig, io::Error> {
     let file = File::open(file_path)?;
     let config: ExerciseConfig = serde_json::from_reader(file)?;
     Ok(config)
 }
 
 
-fn evaluate_exercises_from_config(mode: &str, config: ExerciseConfig, report: &mut Report) {
-    let all_exercises = [config.easy, config.normal, config.hard].concat();
-
-    for exercise in all_exercises {
-        println!("\nEvaluating {}: {}", exercise.exercise_type, exercise.name);
-        let result = evaluate_exercise(&exercise);
+fn evaluate_exercises(grader: &Grader, exercises: Vec<Exercise>, report: &mut Report) -> io::Result<()> {
+    let results = if grader.options.command == Command::Watch {
+        watch::watch(grader, &exercises)?
+    } else if grader.options.jobs == 1 {
+        evaluate_sequentially(grader, &exercises)
+    } else {
+        evaluate_in_parallel(grader, &exercises)
+    };
 
-        let score = if result { exercise.score } else { 0 };
+    // 按配置文件中的顺序写入报告，watch 模式下没有评测过的题目不计入
+    for (exercise, evaluation) in exercises.iter().zip(results) {
+        let Some(evaluation) = evaluation else {
+            continue;
+        };
+        let outcome = evaluation.outcome;
+        let result = outcome == Outcome::Passed;
+        let score = scoring::score(exercise, &evaluation);
+        report.statistics.tests.add(&evaluation.tests);
 
         report.exercises.push(ExerciseResult {
             name: exercise.name.clone(),
             result,
+            outcome,
             score,
+            duration_ms: evaluation.duration_ms,
+            diagnostics: evaluation.diagnostics,
+            tests: evaluation.tests,
+            lints: evaluation.lints,
+            hidden_tests: grader.options.hidden_suite(exercise).is_some(),
         });
 
         if result {
             report.statistics.total_successes += 1;
+        } else if outcome == Outcome::InProgress {
+            report.statistics.total_in_progress += 1;
+        } else if outcome == Outcome::EnvironmentError {
+            report.statistics.total_environment_errors += 1;
         } else {
             report.statistics.total_failures += 1;
         }
 
-        report.statistics.total_score += score;
+        if outcome == Outcome::TimedOut {
+            report.statistics.total_timeouts += 1;
+        }
 
-        if mode == "watch" && !ask_to_continue() {
-            break;
+        if outcome == Outcome::SandboxViolation {
+            report.statistics.total_violations += 1;
         }
+
+        report.statistics.total_score += score;
     }
+
+    Ok(())
 }
 
+// 逐个评测习题
+fn evaluate_sequentially(grader: &Grader, exercises: &[Exercise]) -> Vec<Option<Evaluation>> {
+    let mut results = Vec::new();
 
-fn evaluate_exercise(exercise: &Exercise) -> bool {
-    let exercise_path = PathBuf::from(&format!("./exercises/{}", exercise.path));
-    match exercise.exercise_type.as_str() {
-        "single_file" => evaluate_single_file(&exercise_path),
-        "cargo_project" => evaluate_cargo_project(&exercise_path),
-        _ => {
-            eprintln!("Unknown exercise type: {}", exercise.exercise_type);
-            false
-        }
+    for exercise in exercises {
+        let (evaluation, output) = grader.evaluate(exercise);
+        output.print();
+        results.push(Some(evaluation));
     }
+
+    results
+}
+
+// 使用固定数量的工作线程并行评测习题，每道题评测结束后整体输出
+fn evaluate_in_parallel(grader: &Grader, exercises: &[Exercise]) -> Vec<Option<Evaluation>> {
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<Evaluation>> = exercises.iter().map(|_| None).collect();
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..grader.options.jobs.min(exercises.len()) {
+            let sender = sender.clone();
+            let next = &next;
+            scope.spawn(move || loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(exercise) = exercises.get(index) else {
+                    break;
+                };
+                if sender.send((index, grader.evaluate(exercise))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        for (index, (evaluation, output)) in receiver {
+            output.print();
+            results[index] = Some(evaluation);
+        }
+    });
+
+    results
 }
 
-// 评测单文件 Rust 习题（使用 rustc --test 并执行测试）
-fn evaluate_single_file(file_path: &PathBuf) -> bool {
-    // 获取文件名（不带扩展名）
-    let test_binary = file_path.with_extension(""); 
 
-    // 编译测试文件
-    let compile_output = Command::new("rustc")
-        .arg("--test")  // 使用 rustc --test 进行编译
-        .arg(file_path)
-        .arg("-o")
-        .arg(&test_binary)  // 指定输出文件
-        .output();
+fn evaluate_exercise(exercise: &Exercise, options: &Options, scratch: &Scratch) -> (Evaluation, ExerciseOutput) {
+    let mut output = ExerciseOutput::default();
+    output.info(format!("\nEvaluating {}: {}", exercise.kind.name(), exercise.name));
 
-    if let Ok(output) = compile_output {
-        if output.status.success() {
-            // 编译成功，运行测试二进制文件
-            let test_output = Command::new(&test_binary)
-                .output();
+    // 整道题（编译、测试、clippy）共用同一个截止时间
+    let start = Instant::now();
+    let timeout = exercise.timeout.unwrap_or(options.timeout);
+    let limits = options.sandbox.then(|| exercise.limits.clone().unwrap_or_default());
+    let deadline = start + Duration::from_secs(timeout);
 
-            let test_passed = match test_output {
-                Ok(test_run) => {
-                    if test_run.status.success() {
-                        println!("\x1b[32m{}: TEST PASSED\x1b[0m", file_path.display());
-                        true
-                    } else {
-                        println!("\x1b[31m{}: TEST FAILED\x1b[0m", file_path.display());
-                        false
-                    }
-                }
-                Err(_) => {
-                    eprintln!("Error running test executable for {}", file_path.display());
-                    false
-                }
+    let exercise_path = exercise.source_path();
+    let build_dir = scratch.exercise_dir(&exercise.name);
+    output.debug(format!("Timeout {}s, sandbox {}", timeout, if limits.is_some() { "enabled" } else { "disabled" }));
+    let lints = RefCell::new(Vec::new());
+    let result = match &build_dir {
+        Ok(build_dir) => prepare_source(exercise, options, build_dir, &mut output).and_then(|path| {
+            let context = Context {
+                path: &path,
+                source: &exercise_path,
+                build_dir,
+                deadline,
+                limits: limits.as_ref(),
+                clippy: &exercise.clippy,
+                lints: &lints,
             };
+            exercise.kind.evaluator().evaluate(&context, &mut output)
+        }),
+        Err(e) => Err(setup_failure(format!("Failed to create build directory: {}", e), &mut output)),
+    };
 
-            // 删除测试二进制文件
-            if let Err(e) = fs::remove_file(&test_binary) {
-                eprintln!("Failed to remove test binary {}: {}", test_binary.display(), e);
-            } else {
-                println!("Successfully removed test binary: {}", test_binary.display());
-            }
-
-            return test_passed;
-        } else {
-            // 编译失败
-            eprintln!("\x1b[31m{}: COMPILATION FAILED\x1b[0m", file_path.display());
-            return false;
+    // 删除本题的构建产物
+    if let Ok(build_dir) = build_dir {
+        if let Err(e) = fs::remove_dir_all(&build_dir) {
+            output.warn(format!("Failed to clean up build directory {}: {}", build_dir.display(), e));
         }
-    } else {
-        eprintln!("Error executing rustc --test for {}", file_path.display());
-        return false;
     }
-}
 
-// 评测 Cargo 项目
-fn evaluate_cargo_project(proj_path: &PathBuf) -> bool {
-    let build_success = run_cargo_command(proj_path, "build");
-    let test_success = run_cargo_command(proj_path, "test");
-    let clippy_success = run_cargo_command(proj_path, "clippy");
+    let (mut outcome, diagnostics, tests) = match result {
+        Ok(tests) => (Outcome::Passed, None, tests),
+        Err(failure) => (failure.outcome, Some(*failure.diagnostics), failure.tests),
+    };
 
-    let passed = build_success && test_success && clippy_success;
+    // 报告中的 stderr 是截断过的，完整的编译器输出写入日志文件
+    if let Some(diagnostics) = diagnostics.as_ref().filter(|diagnostics| !diagnostics.full_output.is_empty()) {
+        let stage = diagnostics.stage.map_or("setup", |stage| stage.as_str());
+        output.debug(format!("Full output of the {} stage:\n{}", stage, diagnostics.full_output));
+    }
 
-    if passed {
-        println!("\x1b[32m{}: PASSED\x1b[0m", proj_path.display());
-    } else {
-        println!("\x1b[31m{}: FAILED\x1b[0m", proj_path.display());
+    if outcome == Outcome::TimedOut {
+        output.info(format!("{}: TIMED OUT after {}s", exercise_path.display(), timeout).red().to_string());
     }
 
-    clean_target_directory(proj_path);
+    if outcome == Outcome::EnvironmentError {
+        let reason = diagnostics.as_ref().and_then(|diagnostics| diagnostics.stderr.lines().next()).unwrap_or_default();
+        output.warn(format!("{}: ENVIRONMENT ERROR ({})", exercise_path.display(), reason).yellow().to_string());
+    }
 
-    passed
+    // 学生还没有删除完成标记时，评测结果只作为参考；环境问题仍然如实报告
+    if outcome != Outcome::EnvironmentError && progress::not_done(&exercise_path) {
+        output.info(
+            format!(
+                "{}: IN PROGRESS (remove the `// {}` comment when you are done)",
+                exercise_path.display(),
+                progress::MARKER
+            )
+            .yellow()
+            .to_string(),
+        );
+        outcome = Outcome::InProgress;
+    }
+
+    print_test_summary(&tests, &mut output);
+    let lints = lints.into_inner();
+    print_lints(exercise, outcome, &lints, &mut output);
+
+    let evaluation = Evaluation {
+        outcome,
+        duration_ms: start.elapsed().as_millis() as u64,
+        diagnostics,
+        tests,
+        lints,
+    };
+
+    (evaluation, output)
 }
 
-// 运行 Cargo 命令
-fn run_cargo_command(proj_path: &PathBuf, command: &str) -> bool {
-    let output = Command::new("cargo")
-        .arg(command)
-        .current_dir(proj_path)
-        .output();
+// 评测时使用的题目路径：有隐藏测试时为构建目录中放入了隐藏测试的副本，否则为题目本身
+fn prepare_source(
+    exercise: &Exercise,
+    options: &Options,
+    build_dir: &Path,
+    output: &mut ExerciseOutput,
+) -> Result<PathBuf, StageFailure> {
+    let Some(suite) = options.hidden_suite(exercise) else {
+        return Ok(exercise.source_path());
+    };
+
+    output.info(format!("Using hidden tests from {}", suite.display()));
+    hidden::overlay(&exercise.source_path(), &suite, &build_dir.join("source"))
+        .map_err(|e| setup_failure(format!("Failed to apply hidden tests from {}: {}", suite.display(), e), output))
+}
 
-    match output {
-        Ok(out) => out.status.success(),
-        Err(_) => false,
+// 还没有开始编译就失败（例如无法创建构建目录）
+fn setup_failure(message: String, output: &mut ExerciseOutput) -> StageFailure {
+    output.warn(message.clone());
+    StageFailure {
+        outcome: Outcome::Failed,
+        diagnostics: Box::new(Diagnostics { stderr: message, ..Diagnostics::default() }),
+        tests: Vec::new(),
     }
 }
 
-// 清理 target 目录
-fn clean_target_directory(proj_path: &PathBuf) {
-    let target_dir = proj_path.join("target");
+// 输出各个测试的统计，并列出未通过的测试
+fn print_test_summary(tests: &[TestCase], output: &mut ExerciseOutput) {
+    if tests.is_empty() {
+        return;
+    }
+
+    let mut summary = TestSummary::default();
+    summary.add(tests);
+    output.info(format!(
+        "Tests: {} passed, {} failed, {} ignored",
+        summary.passed, summary.failed, summary.ignored
+    ));
 
-    if target_dir.exists() {
-        if let Err(e) = fs::remove_dir_all(&target_dir) {
-            eprintln!("Failed to clean up target directory: {}", e);
-        } else {
-            println!("Successfully cleaned up target directory in: {}", proj_path.display());
+    for test in tests.iter().filter(|test| test.status != TestStatus::Failed) {
+        let status = if test.status == TestStatus::Passed { "passed" } else { "ignored" };
+        output.trace(format!("  {} {}", status, test.name));
+    }
+    for test in tests.iter().filter(|test| test.status == TestStatus::Failed) {
+        match test.duration_ms {
+            Some(duration_ms) => output.info(format!("  FAILED {} ({:.3}ms)", test.name, duration_ms).red().to_string()),
+            None => output.info(format!("  FAILED {}", test.name).red().to_string()),
         }
     }
 }
 
-// 用户确认是否继续
-fn ask_to_continue() -> bool {
-    let mut input = String::new();
-    println!("\nPress any key to continue, or 'q' to quit.");
-    io::stdin().read_line(&mut input).unwrap();
-    input.trim().to_lowercase() != "q"
+// 列出 clippy 发现的问题，通过的题目按规则扣分时一并说明
+fn print_lints(exercise: &Exercise, outcome: Outcome, lints: &[Finding], output: &mut ExerciseOutput) {
+    if lints.is_empty() {
+        return;
+    }
+
+    output.info(format!("Clippy: {} finding(s)", lints.len()));
+    for finding in lints {
+        let line = format!(
+            "  {} {}{}: {}",
+            if finding.level == LintLevel::Error { "error" } else { "warning" },
+            finding.location().map_or(String::new(), |location| format!("{} ", location)),
+            finding.lint.as_deref().unwrap_or("rustc"),
+            finding.message
+        );
+        output.info(if finding.level == LintLevel::Error { line.red() } else { line.yellow() }.to_string());
+    }
+
+    let deduction = exercise.clippy.deduction(lints);
+    if outcome == Outcome::Passed && deduction > 0.0 {
+        let points = deduction.min(exercise.score as f64);
+        output.info(format!("-{} point(s) for clippy errors", points).yellow().to_string());
+    }
 }
 
 // 保存评测报告
-fn save_report_to_json(file_name: &str, report: &Report) -> io::Result<()> {
-    let file = File::create(file_name)?;
-    serde_json::to_writer_pretty(file, report)?;
-    Ok(())
+fn save_report(file_name: &str, format: Format, report: &Report) -> io::Result<()> {
+    let content = formats::render(format, report)?;
+    fs::write(file_name, content)
 }
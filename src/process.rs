@@ -0,0 +1,156 @@
+use std::io::{self, Read, Write};
+use std::process::{Child, Command, ExitStatus, Output, Stdio};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+// 轮询子进程状态的最大间隔；刚启动时间隔从 1ms 开始逐渐加倍，短时间运行的程序可以及时得到结果
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// 正在运行的子进程，收到 Ctrl-C 时需要连同它们的进程组一起杀死
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+
+// 子进程的执行结果：正常结束（无论退出码如何）或超时被杀死，超时时保留已经产生的输出。
+// 正常结束时同时返回子进程使用的 CPU 时间（用户态与内核态之和，无法获取时为 0）
+pub enum CommandOutcome {
+    Finished(Output, Duration),
+    TimedOut { stdout: Vec<u8>, stderr: Vec<u8> },
+}
+
+// 运行命令并捕获输出，到达 deadline 时杀死子进程及其派生的所有进程
+pub fn run_with_deadline(command: &mut Command, deadline: Instant) -> io::Result<CommandOutcome> {
+    run_with_input(command, None, deadline)
+}
+
+// 与 run_with_deadline 相同，input 不为空时写入子进程的标准输入
+pub fn run_with_input(command: &mut Command, input: Option<&[u8]>, deadline: Instant) -> io::Result<CommandOutcome> {
+    command
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    // 让子进程成为新进程组的组长，超时时可以一次杀死 cargo 及其启动的测试程序
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        command.process_group(0);
+    }
+
+    let mut child = command.spawn()?;
+    RUNNING.lock().unwrap().push(child.id());
+    if let (Some(input), Some(stdin)) = (input, child.stdin.take()) {
+        write_in_background(stdin, input.to_vec());
+    }
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let waited = wait_until(&mut child, deadline);
+    RUNNING.lock().unwrap().retain(|&id| id != child.id());
+    let waited = waited?;
+
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    Ok(match waited {
+        Some((status, cpu_time)) => CommandOutcome::Finished(Output { status, stdout, stderr }, cpu_time),
+        None => CommandOutcome::TimedOut { stdout, stderr },
+    })
+}
+
+// 等待子进程结束并返回其退出状态与 CPU 时间，到达 deadline 时杀死子进程并返回 None
+fn wait_until(child: &mut Child, deadline: Instant) -> io::Result<Option<(ExitStatus, Duration)>> {
+    let mut interval = Duration::from_millis(1);
+    loop {
+        if let Some(finished) = try_wait(child)? {
+            return Ok(Some(finished));
+        }
+        if Instant::now() >= deadline {
+            kill_process_tree(child);
+            child.wait()?;
+            return Ok(None);
+        }
+        thread::sleep(interval);
+        interval = (interval * 2).min(POLL_INTERVAL);
+    }
+}
+
+// 子进程已经结束时回收它。Unix 上使用 wait4 以便同时得到子进程的资源使用情况
+#[cfg(unix)]
+fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Duration)>> {
+    use std::os::unix::process::ExitStatusExt;
+
+    let mut status = 0;
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    match unsafe { libc::wait4(child.id() as libc::pid_t, &mut status, libc::WNOHANG, &mut usage) } {
+        0 => Ok(None),
+        -1 => {
+            let error = io::Error::last_os_error();
+            if error.kind() == io::ErrorKind::Interrupted { Ok(None) } else { Err(error) }
+        }
+        _ => {
+            let time = |time: libc::timeval| Duration::new(time.tv_sec as u64, time.tv_usec as u32 * 1000);
+            Ok(Some((ExitStatus::from_raw(status), time(usage.ru_utime) + time(usage.ru_stime))))
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn try_wait(child: &mut Child) -> io::Result<Option<(ExitStatus, Duration)>> {
+    Ok(child.try_wait()?.map(|status| (status, Duration::ZERO)))
+}
+
+// 杀死所有正在运行的子进程及其派生的进程
+#[cfg(unix)]
+pub fn kill_all() {
+    for id in RUNNING.lock().unwrap().drain(..) {
+        unsafe {
+            libc::kill(-(id as libc::pid_t), libc::SIGKILL);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+pub fn kill_all() {}
+
+// 子进程被信号终止时返回信号编号
+#[cfg(unix)]
+pub fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+pub fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+// 在后台线程中读取管道，避免子进程因管道写满而阻塞
+fn read_in_background<R: Read + Send + 'static>(pipe: Option<R>) -> JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut buffer).ok();
+        }
+        buffer
+    })
+}
+
+// 在后台线程中写入标准输入，写完后关闭管道；子进程提前退出时忽略写入错误
+fn write_in_background<W: Write + Send + 'static>(mut pipe: W, input: Vec<u8>) {
+    thread::spawn(move || {
+        pipe.write_all(&input).ok();
+    });
+}
+
+#[cfg(unix)]
+fn kill_process_tree(child: &mut Child) {
+    // 子进程的进程组号等于其 pid，向整个进程组发送 SIGKILL
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+#[cfg(not(unix))]
+fn kill_process_tree(child: &mut Child) {
+    child.kill().ok();
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::selection::Difficulty;
+use crate::{ExerciseConfig, ExerciseResult, Outcome};
+
+// 与 rustlings 相同的完成标记：题目的源文件中还有 `// I AM NOT DONE` 这一行时，题目视为仍在进行中
+pub const MARKER: &str = "I AM NOT DONE";
+
+// 某个难度下各状态的题目数
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DifficultyProgress {
+    pub difficulty: Difficulty,
+    pub done: usize,
+    pub in_progress: usize,
+    pub failing: usize,
+}
+
+// 单文件题目检查该文件，Cargo 项目检查目录下（target 除外）所有的 .rs 文件
+pub fn not_done(path: &Path) -> bool {
+    if path.is_file() {
+        return fs::read_to_string(path).is_ok_and(|source| has_marker(&source));
+    }
+
+    let Ok(entries) = fs::read_dir(path) else {
+        return false;
+    };
+    entries.flatten().map(|entry| entry.path()).any(|path| {
+        if path.is_dir() {
+            !path.ends_with("target") && not_done(&path)
+        } else {
+            path.extension().is_some_and(|extension| extension == "rs") && not_done(&path)
+        }
+    })
+}
+
+fn has_marker(source: &str) -> bool {
+    source
+        .lines()
+        .any(|line| line.trim().strip_prefix("//").is_some_and(|comment| comment.trim() == MARKER))
+}
+
+// 按难度统计已完成、进行中与未通过的题目，只包含本次评测过的难度
+pub fn by_difficulty(config: &ExerciseConfig, results: &[ExerciseResult]) -> Vec<DifficultyProgress> {
+    [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard]
+        .into_iter()
+        .filter_map(|difficulty| {
+            let mut progress = DifficultyProgress { difficulty, done: 0, in_progress: 0, failing: 0 };
+            let names = config
+                .exercises()
+                .filter(|(exercise_difficulty, _)| *exercise_difficulty == difficulty)
+                .map(|(_, exercise)| &exercise.name);
+            for name in names {
+                match results.iter().find(|result| result.name == *name).map(|result| result.outcome) {
+                    Some(Outcome::Passed) => progress.done += 1,
+                    Some(Outcome::InProgress) => progress.in_progress += 1,
+                    // 因环境问题没能评测的题目不计入任何一类
+                    Some(Outcome::EnvironmentError) | None => {}
+                    Some(_) => progress.failing += 1,
+                }
+            }
+            (progress.done + progress.in_progress + progress.failing > 0).then_some(progress)
+        })
+        .collect()
+}
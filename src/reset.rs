@@ -0,0 +1,83 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Exercise;
+use crate::files::copy_tree;
+use crate::history::civil_from_days;
+
+// 题目模板的默认目录，结构与 exercises/ 相同，例如 `templates/easy/algorithm11.rs`
+pub const TEMPLATES_DIR: &str = "templates";
+
+// 重置前的备份目录，每次重置使用以时间命名的子目录
+pub const BACKUP_DIR: &str = ".grader-backups";
+
+// 题目对应的模板，不存在时返回 None
+pub fn template(root: &Path, exercise: &Exercise) -> Option<PathBuf> {
+    let template = root.join(&exercise.path);
+    template.exists().then_some(template)
+}
+
+// 备份题目的当前版本，然后用模板覆盖题目，返回备份的路径（题目文件已被删除时没有备份）。
+// Cargo 项目先删除项目中除 target 以外的所有文件，学生新建的文件同样会被移除（但保留在备份中）
+pub fn reset(exercise: &Exercise, template: &Path, backup_root: &Path) -> io::Result<Option<PathBuf>> {
+    let source = exercise.source_path();
+    if source.exists() && template.is_dir() != source.is_dir() {
+        let expected = if source.is_dir() { "a directory" } else { "a file" };
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("template {} is not {}", template.display(), expected),
+        ));
+    }
+    let existed = source.exists();
+    let backup = backup_path(backup_root, exercise);
+
+    if template.is_dir() {
+        if source.is_dir() {
+            copy_tree(&source, &backup)?;
+        }
+        fs::create_dir_all(&source)?;
+        for entry in fs::read_dir(&source)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                if !path.ends_with("target") {
+                    fs::remove_dir_all(&path)?;
+                }
+            } else {
+                fs::remove_file(&path)?;
+            }
+        }
+        copy_tree(template, &source)?;
+    } else {
+        if existed {
+            if let Some(parent) = backup.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::copy(&source, &backup)?;
+        }
+        fs::copy(template, &source)?;
+    }
+
+    Ok(existed.then_some(backup))
+}
+
+// 本次重置的备份位置。同一秒内重复重置同一道题时在目录名后加序号，避免覆盖之前的备份
+fn backup_path(backup_root: &Path, exercise: &Exercise) -> PathBuf {
+    let timestamp = timestamp();
+    let mut backup = backup_root.join(&timestamp).join(&exercise.path);
+    let mut index = 2;
+    while backup.exists() {
+        backup = backup_root.join(format!("{}-{}", timestamp, index)).join(&exercise.path);
+        index += 1;
+    }
+    backup
+}
+
+// 当前的 UTC 时间，形如 `20261018-093015`
+fn timestamp() -> String {
+    let secs = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let (year, month, day) = civil_from_days((secs / 86400) as i64);
+    let time = secs % 86400;
+    format!("{:04}{:02}{:02}-{:02}{:02}{:02}", year, month, day, time / 3600, time % 3600 / 60, time % 60)
+}
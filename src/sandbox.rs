@@ -0,0 +1,249 @@
+use serde::{Deserialize, Serialize};
+use std::iter;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+use tempfile::TempDir;
+
+use crate::libtest::TestCase;
+
+// 运行学生代码时的资源限制，可以在 exercise_config.json 中通过 `limits` 为单道题覆盖，例如
+// `"limits": { "cpu_secs": 120, "memory_mb": 8192 }`
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct Limits {
+    // CPU 时间（秒），所有测试线程共享
+    pub cpu_secs: u64,
+    // 地址空间（MiB）
+    pub memory_mb: u64,
+    // 单个文件的最大大小（MiB）
+    pub file_size_mb: u64,
+    // 进程数（包括线程），以 root 运行时不生效。Linux 上在新的用户命名空间中只统计测试程序自己创建的进程；
+    // 系统禁用了用户命名空间时按用户统计会把评测程序与 cargo 的进程也算进去，此时不限制
+    pub processes: u64,
+    // 是否允许访问网络；不允许时在新的网络命名空间中运行
+    pub network: bool,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            cpu_secs: 60,
+            memory_mb: 4096,
+            file_size_mb: 64,
+            processes: 1024,
+            network: false,
+        }
+    }
+}
+
+// 超出资源限制的类型
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Violation {
+    CpuTime,
+    Memory,
+    FileSize,
+    Processes,
+}
+
+impl Violation {
+    pub fn description(&self) -> &'static str {
+        match self {
+            Violation::CpuTime => "CPU time limit exceeded",
+            Violation::Memory => "memory limit exceeded",
+            Violation::FileSize => "file size limit exceeded",
+            Violation::Processes => "process limit exceeded",
+        }
+    }
+}
+
+// 隔离运行测试程序的环境：私有的临时工作目录与资源限制，drop 时删除临时目录
+pub struct Sandbox {
+    dir: TempDir,
+    limits: Limits,
+}
+
+impl Sandbox {
+    pub fn new(limits: Limits) -> std::io::Result<Self> {
+        let dir = tempfile::Builder::new().prefix("grader-sandbox-").tempdir()?;
+        Ok(Sandbox { dir, limits })
+    }
+
+    // 让命令在沙箱中运行；工作目录会改变，程序路径需要是绝对路径
+    pub fn apply(&self, command: &mut Command) {
+        self.apply_in(command, self.dir.path());
+    }
+
+    // 与 apply 相同，但在 dir 中运行，用于需要读取项目中数据文件的 Cargo 项目测试程序与程序
+    pub fn apply_in(&self, command: &mut Command, dir: &Path) {
+        command
+            .current_dir(dir)
+            .env("HOME", self.dir.path())
+            .env("TMPDIR", self.dir.path())
+            // glibc 为每个线程预留的 malloc arena 会占用大量地址空间，限制数量以免测试线程较多时误判超出内存限制
+            .env("MALLOC_ARENA_MAX", "2");
+        self.limit(command);
+    }
+
+    // 只施加资源限制与网络隔离，工作目录与环境变量不变。
+    // 用于 cargo test --doc：cargo 需要原来的 HOME 才能找到工具链，文档测试由 rustdoc 编译并运行
+    pub fn limit(&self, command: &mut Command) {
+        #[cfg(target_os = "linux")]
+        set_limits(command, self.limits.clone());
+        #[cfg(not(target_os = "linux"))]
+        let _ = command;
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_limits(command: &mut Command, limits: Limits) {
+    use std::os::unix::process::CommandExt;
+
+    const MIB: u64 = 1024 * 1024;
+
+    // pre_exec 在 fork 之后、exec 之前执行，只调用 async-signal-safe 的系统调用
+    unsafe {
+        command.pre_exec(move || {
+            // 软限制到达时发送 SIGXCPU，一秒后硬限制发送 SIGKILL
+            set_limit(libc::RLIMIT_CPU, limits.cpu_secs, limits.cpu_secs + 1)?;
+            set_limit(libc::RLIMIT_AS, limits.memory_mb * MIB, limits.memory_mb * MIB)?;
+            set_limit(libc::RLIMIT_FSIZE, limits.file_size_mb * MIB, limits.file_size_mb * MIB)?;
+            libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0);
+
+            // 在新的用户命名空间中运行：内核（5.14 起）按用户命名空间分别统计 RLIMIT_NPROC，
+            // 命名空间中只有测试程序及其派生的进程，不会把同一用户的评测程序、cargo 与其他题目的进程算进去。
+            // 必须先创建命名空间再设置限制，外层仍按原来的限制统计
+            let network = if limits.network { 0 } else { libc::CLONE_NEWNET };
+            if libc::unshare(libc::CLONE_NEWUSER | network) == 0 {
+                set_limit(libc::RLIMIT_NPROC, limits.processes, limits.processes)?;
+            } else if !limits.network {
+                // 系统禁用了用户命名空间：root 仍然可以隔离网络，失败时不隔离
+                libc::unshare(libc::CLONE_NEWNET);
+            }
+
+            Ok(())
+        });
+    }
+}
+
+#[cfg(all(target_os = "linux", target_env = "gnu"))]
+type Resource = libc::__rlimit_resource_t;
+#[cfg(all(target_os = "linux", not(target_env = "gnu")))]
+type Resource = libc::c_int;
+
+// 只会收紧限制：非特权进程不能提高硬限制，已有的限制更严格时保持不变
+#[cfg(target_os = "linux")]
+fn set_limit(resource: Resource, soft: u64, hard: u64) -> std::io::Result<()> {
+    let mut current = libc::rlimit { rlim_cur: 0, rlim_max: 0 };
+    if unsafe { libc::getrlimit(resource, &mut current) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+
+    let hard = current.rlim_max.min(hard as libc::rlim_t);
+    let limit = libc::rlimit {
+        rlim_cur: hard.min(soft as libc::rlim_t),
+        rlim_max: hard,
+    };
+    if unsafe { libc::setrlimit(resource, &limit) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+// 根据终止信号、CPU 时间与输出判断测试程序是否因为超出资源限制而失败
+pub fn violation(
+    limits: &Limits,
+    signal: Option<i32>,
+    cpu_time: Duration,
+    stderr: &str,
+    tests: &[TestCase],
+) -> Option<Violation> {
+    // CPU 时间的软限制发送 SIGXCPU，忽略它的程序在硬限制处被 SIGKILL 杀死。
+    // 其他来源的 SIGKILL（例如内核的 OOM killer）无法判断超出了哪一项限制，只记录终止信号
+    #[cfg(unix)]
+    match signal {
+        Some(libc::SIGXCPU) => return Some(Violation::CpuTime),
+        Some(libc::SIGKILL) if cpu_time >= Duration::from_secs(limits.cpu_secs) => return Some(Violation::CpuTime),
+        Some(libc::SIGXFSZ) => return Some(Violation::FileSize),
+        _ => {}
+    }
+    #[cfg(not(unix))]
+    let _ = (limits, signal, cpu_time);
+
+    let messages = iter::once(stderr).chain(tests.iter().filter_map(|test| test.message.as_deref()));
+    for message in messages {
+        if message.contains("memory allocation of") {
+            return Some(Violation::Memory);
+        }
+        if message.contains("failed to spawn thread") {
+            return Some(Violation::Processes);
+        }
+    }
+
+    None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sigkill_is_a_cpu_violation_only_at_the_cpu_limit() {
+        let limits = Limits { cpu_secs: 2, ..Limits::default() };
+        let violation = |signal, millis| violation(&limits, Some(signal), Duration::from_millis(millis), "", &[]);
+
+        assert_eq!(violation(libc::SIGXCPU, 1999), Some(Violation::CpuTime));
+        assert_eq!(violation(libc::SIGKILL, 2001), Some(Violation::CpuTime));
+        // 例如被 OOM killer 杀死
+        assert_eq!(violation(libc::SIGKILL, 150), None);
+        assert_eq!(violation(libc::SIGXFSZ, 0), Some(Violation::FileSize));
+    }
+
+    // 在沙箱中运行 sh 脚本，返回是否成功与 stderr
+    #[cfg(target_os = "linux")]
+    fn run_script(limits: Limits, script: &str) -> (bool, String) {
+        let sandbox = Sandbox::new(limits).unwrap();
+        let mut command = Command::new("sh");
+        command.args(["-c", script]);
+        sandbox.apply(&mut command);
+        let out = command.output().unwrap();
+        (out.status.success(), String::from_utf8_lossy(&out.stderr).into_owned())
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn process_limit_does_not_count_processes_outside_the_sandbox() {
+        // 同一用户已经有许多进程（例如并行评测的其他题目），测试程序自己只创建了几个
+        let mut others: Vec<_> = (0..16).map(|_| Command::new("sleep").arg("5").spawn().unwrap()).collect();
+        let limits = Limits { processes: 8, ..Limits::default() };
+        let (success, stderr) = run_script(limits, "sleep 0.1 & sleep 0.1 & wait");
+        for other in &mut others {
+            other.kill().ok();
+            other.wait().ok();
+        }
+
+        assert!(success, "{}", stderr);
+        assert_eq!(violation(&Limits::default(), None, Duration::ZERO, &stderr, &[]), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn process_limit_applies_to_the_sandboxed_program() {
+        // 内核不对 root 检查进程数限制
+        if unsafe { libc::geteuid() } == 0 {
+            return;
+        }
+        let limits = Limits { processes: 4, ..Limits::default() };
+        let (_, stderr) = run_script(limits, "for i in 1 2 3 4 5 6 7 8; do sleep 0.2 & done; wait");
+        assert!(stderr.contains("fork"), "{}", stderr);
+    }
+
+    #[test]
+    fn memory_and_thread_failures_are_read_from_the_output() {
+        let limits = Limits::default();
+        let stderr = "memory allocation of 1073741824 bytes failed\n";
+        assert_eq!(violation(&limits, Some(libc::SIGABRT), Duration::ZERO, stderr, &[]), Some(Violation::Memory));
+        assert_eq!(violation(&limits, None, Duration::ZERO, "assertion failed", &[]), None);
+    }
+}
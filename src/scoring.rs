@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::diagnostics::Stage;
+use crate::libtest::{TestCase, TestStatus};
+use crate::{Evaluation, Exercise, Outcome};
+
+// 题目的计分方式，在 exercise_config.json 中通过 `scoring` 字段配置，例如
+// `"scoring": { "policy": "proportional" }` 或
+// `"scoring": { "policy": "weighted", "weights": { "test_sum": 2, "test_empty": 1 } }`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(tag = "policy", rename_all = "snake_case")]
+pub enum ScoringPolicy {
+    // 全部通过得满分，否则 0 分
+    #[default]
+    AllOrNothing,
+    // 按通过测试的比例给分
+    Proportional,
+    // 按通过测试的权重之和给分；测试名可以是完整路径或最后一段，未列出的测试不计分
+    Weighted { weights: BTreeMap<String, f64> },
+}
+
+impl ScoringPolicy {
+    pub fn is_all_or_nothing(&self) -> bool {
+        *self == ScoringPolicy::AllOrNothing
+    }
+}
+
+// 计算题目得分。只有在测试阶段失败时才按测试结果给部分分，
+// 编译失败、超时或 clippy 未通过仍然记 0 分
+pub fn score(exercise: &Exercise, evaluation: &Evaluation) -> f64 {
+    let full = exercise.score as f64;
+
+    // clippy 规则设置了 deduct 时，lint 错误按条扣分
+    if evaluation.outcome == Outcome::Passed {
+        return (full - exercise.clippy.deduction(&evaluation.lints)).max(0.0);
+    }
+
+    let failed_in_tests = evaluation.outcome == Outcome::Failed
+        && evaluation
+            .diagnostics
+            .as_ref()
+            .is_some_and(|diagnostics| diagnostics.stage == Some(Stage::Test));
+    if !failed_in_tests {
+        return 0.0;
+    }
+
+    let fraction = match &exercise.scoring {
+        ScoringPolicy::AllOrNothing => 0.0,
+        ScoringPolicy::Proportional => proportional(
+            &evaluation.tests,
+            evaluation.diagnostics.as_ref().and_then(|diagnostics| diagnostics.expected_tests),
+        ),
+        ScoringPolicy::Weighted { weights } => weighted(weights, &evaluation.tests),
+    };
+
+    // 保留两位小数
+    (full * fraction * 100.0).round() / 100.0
+}
+
+// expected 为测试程序宣布要运行的测试总数。测试程序崩溃时还没有运行的测试没有结果，同样视为未通过
+fn proportional(tests: &[TestCase], expected: Option<u32>) -> f64 {
+    let ignored = tests.iter().filter(|test| test.status == TestStatus::Ignored).count();
+    let counted = (tests.len() - ignored).max((expected.unwrap_or(0) as usize).saturating_sub(ignored));
+    if counted == 0 {
+        return 0.0;
+    }
+    let passed = tests.iter().filter(|test| test.status == TestStatus::Passed).count();
+    passed as f64 / counted as f64
+}
+
+// 配置中列出但没有运行的测试（例如被删除）视为未通过
+fn weighted(weights: &BTreeMap<String, f64>, tests: &[TestCase]) -> f64 {
+    let total: f64 = weights.values().sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+
+    let earned: f64 = weights
+        .iter()
+        .filter(|(name, _)| {
+            tests.iter().any(|test| {
+                test.status == TestStatus::Passed
+                    && (test.name == **name || test.name.rsplit("::").next() == Some(name.as_str()))
+            })
+        })
+        .map(|(_, weight)| weight)
+        .sum();
+
+    earned / total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::libtest;
+
+    fn case(name: &str, status: TestStatus) -> TestCase {
+        TestCase { name: name.to_string(), status, message: None, duration_ms: None }
+    }
+
+    #[test]
+    fn proportional_counts_passed_tests() {
+        let tests = [case("a", TestStatus::Passed), case("b", TestStatus::Failed), case("c", TestStatus::Ignored)];
+        assert_eq!(proportional(&tests, Some(3)), 0.5);
+        assert_eq!(proportional(&tests, None), 0.5);
+        assert_eq!(proportional(&[], None), 0.0);
+    }
+
+    #[test]
+    fn proportional_counts_tests_that_never_reported() {
+        // 测试程序在运行 c 时崩溃，c、d、e 都没有结果
+        let tests = [case("a", TestStatus::Passed), case("b", TestStatus::Passed)];
+        assert_eq!(proportional(&tests, Some(5)), 0.4);
+    }
+
+    #[test]
+    fn crashed_test_binary_does_not_get_full_marks() {
+        // test_sort_2 栈溢出，测试程序被 SIGABRT 终止，test_sort_3 没有开始运行
+        let stdout = [
+            r#"{ "type": "suite", "event": "started", "test_count": 3 }"#,
+            r#"{ "type": "test", "event": "started", "name": "tests::test_sort_1" }"#,
+            r#"{ "type": "test", "event": "started", "name": "tests::test_sort_2" }"#,
+            r#"{ "type": "test", "name": "tests::test_sort_1", "event": "ok", "exec_time": 0.0001 }"#,
+        ]
+        .join("\n");
+
+        let tests = libtest::parse_output(stdout.as_bytes());
+        assert_eq!(tests.len(), 2);
+        assert_eq!(tests[1].name, "tests::test_sort_2");
+        assert_eq!(tests[1].status, TestStatus::Failed);
+
+        let expected = libtest::expected_count(stdout.as_bytes());
+        assert_eq!(expected, Some(3));
+        assert_eq!((proportional(&tests, expected) * 100.0).round() / 100.0, 0.33);
+        assert_eq!(proportional(&tests, None), 0.5);
+    }
+
+    #[test]
+    fn expected_count_reads_plain_text_output() {
+        let stdout = "\nrunning 1 test\ntest a ... ok\n\nrunning 4 tests\n";
+        assert_eq!(libtest::expected_count(stdout.as_bytes()), Some(5));
+        assert_eq!(libtest::expected_count(b"error: could not compile"), None);
+    }
+}
@@ -0,0 +1,46 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::process::exit;
+use tempfile::TempDir;
+
+use crate::process;
+
+// 本次运行的临时构建目录：单文件题目的测试程序与 Cargo 项目的 target 目录都放在这里，
+// 评测过程不会向 exercises/ 中写入任何文件。drop 时删除整个目录
+pub struct Scratch {
+    dir: TempDir,
+}
+
+impl Scratch {
+    pub fn new() -> io::Result<Self> {
+        let dir = tempfile::Builder::new().prefix("grader-run-").tempdir()?;
+        Ok(Scratch { dir })
+    }
+
+    // 每道题使用独立的子目录，并行评测时互不干扰；重新评测时先清空上一次的产物
+    pub fn exercise_dir(&self, name: &str) -> io::Result<PathBuf> {
+        let name: String = name
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+            .collect();
+        let dir = self.dir.path().join(name);
+        if dir.exists() {
+            fs::remove_dir_all(&dir)?;
+        }
+        fs::create_dir_all(&dir)?;
+        Ok(dir)
+    }
+
+    // 收到 Ctrl-C 时杀死正在运行的编译与测试进程并删除临时目录。
+    // 子进程位于独立的进程组中，不会收到终端发出的 SIGINT
+    pub fn clean_up_on_interrupt(&self) -> Result<(), ctrlc::Error> {
+        let dir = self.dir.path().to_path_buf();
+        ctrlc::set_handler(move || {
+            process::kill_all();
+            fs::remove_dir_all(&dir).ok();
+            log::warn!("\nInterrupted, build directory removed");
+            exit(130);
+        })
+    }
+}
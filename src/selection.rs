@@ -0,0 +1,170 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Exercise, ExerciseConfig};
+
+// 题目难度，对应 exercise_config.json 中的三个分组
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Unknown difficulty '{}', expected one of: easy, normal, hard", s)),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Difficulty::Easy => write!(f, "easy"),
+            Difficulty::Normal => write!(f, "normal"),
+            Difficulty::Hard => write!(f, "hard"),
+        }
+    }
+}
+
+// 本次要评测的题目范围，所有条件为空时评测全部题目；会原样写入报告
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Selection {
+    // 按名称（或路径）指定的题目
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub names: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub difficulties: Vec<Difficulty>,
+    // 匹配题目名称或路径的通配符，支持 `*` 与 `?`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub globs: Vec<String>,
+    // 匹配题目名称或路径的正则表达式
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub regexes: Vec<String>,
+}
+
+impl Selection {
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty() && self.difficulties.is_empty() && self.globs.is_empty() && self.regexes.is_empty()
+    }
+
+    // 按配置文件中的顺序筛选题目；指定的题目名不存在或没有题目满足条件时返回错误
+    pub fn select(&self, config: &ExerciseConfig) -> Result<Vec<Exercise>, String> {
+        let regexes = self
+            .regexes
+            .iter()
+            .map(|pattern| Regex::new(pattern).map_err(|e| format!("Invalid --regex '{}': {}", pattern, e)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for name in &self.names {
+            if !config.exercises().any(|(_, exercise)| matches_name(name, exercise)) {
+                return Err(format!("No exercise named '{}' in the config", name));
+            }
+        }
+
+        let selected: Vec<Exercise> = config
+            .exercises()
+            .filter(|(difficulty, exercise)| {
+                let targets = [exercise.name.as_str(), exercise.path.as_str()];
+
+                (self.names.is_empty() || self.names.iter().any(|name| matches_name(name, exercise)))
+                    && (self.difficulties.is_empty() || self.difficulties.contains(difficulty))
+                    && (self.globs.is_empty()
+                        || self.globs.iter().any(|glob| targets.iter().any(|target| glob_match(glob, target))))
+                    && (regexes.is_empty()
+                        || regexes.iter().any(|regex| targets.iter().any(|target| regex.is_match(target))))
+            })
+            .map(|(_, exercise)| exercise.clone())
+            .collect();
+
+        if selected.is_empty() {
+            return Err("No exercises match the given filters".to_string());
+        }
+
+        Ok(selected)
+    }
+}
+
+// 题目名可以写完整名称（`algorithm7.rs`）、不带扩展名的名称或配置中的路径
+fn matches_name(name: &str, exercise: &Exercise) -> bool {
+    exercise.name == name
+        || exercise.path == name
+        || exercise.name.strip_suffix(".rs") == Some(name)
+}
+
+// 简单的通配符匹配：`*` 匹配任意长度字符，`?` 匹配单个字符
+fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None;
+
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            // 让上一个 `*` 多匹配一个字符后重试
+            p = star + 1;
+            t = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn star_matches_any_run_of_characters() {
+        assert!(glob_match("algorithm*", "algorithm7.rs"));
+        assert!(glob_match("*.rs", "algorithm7.rs"));
+        assert!(glob_match("a*m*.rs", "algorithm7.rs"));
+        assert!(glob_match("*", ""));
+        assert!(glob_match("**", "anything"));
+        assert!(glob_match("normal/*", "normal/solution1"));
+        assert!(glob_match("*ab", "aab"));
+        assert!(!glob_match("*.rs", "solution1"));
+    }
+
+    #[test]
+    fn question_mark_matches_exactly_one_character() {
+        assert!(glob_match("algorithm?.rs", "algorithm7.rs"));
+        assert!(!glob_match("algorithm?.rs", "algorithm12.rs"));
+        assert!(!glob_match("?", ""));
+        assert!(glob_match("??", "ab"));
+    }
+
+    #[test]
+    fn patterns_match_the_whole_text() {
+        assert!(glob_match("algorithm7.rs", "algorithm7.rs"));
+        assert!(!glob_match("algorithm", "algorithm7.rs"));
+        assert!(!glob_match("7.rs", "algorithm7.rs"));
+        assert!(!glob_match("algorithm7.rs", "algorithm7.rs.bak"));
+    }
+
+    #[test]
+    fn empty_pattern_matches_only_empty_text() {
+        assert!(glob_match("", ""));
+        assert!(!glob_match("", "algorithm7.rs"));
+    }
+}
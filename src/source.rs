@@ -0,0 +1,235 @@
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+// `#[cfg(test)]` 标注的内联模块的开头，属性与 mod 之间可以有其他属性
+static TEST_MODULE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"#\s*\[\s*cfg\s*\(\s*test\s*\)\s*\]\s*(#\s*\[[^\]]*\]\s*)*(pub(\s*\([^)]*\))?\s+)?mod\s+\w+\s*\{").unwrap()
+});
+
+// 将注释、字符串与字符字面量的内容替换为空格，长度与原文相同，之后可以直接在结果中按位置查找代码结构
+pub fn blank_non_code(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut code = bytes.to_vec();
+    let mut blank = |range: Range<usize>| {
+        for byte in &mut code[range] {
+            if *byte != b'\n' {
+                *byte = b' ';
+            }
+        }
+    };
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &bytes[i..];
+        let end = if rest.starts_with(b"//") {
+            rest.iter().position(|&byte| byte == b'\n').map_or(bytes.len(), |offset| i + offset)
+        } else if rest.starts_with(b"/*") {
+            block_comment_end(bytes, i)
+        } else if let Some(end) = raw_string_end(bytes, i) {
+            end
+        } else if rest[0] == b'"' {
+            quoted_end(bytes, i, b'"')
+        } else if rest[0] == b'\'' && is_char_literal(rest) {
+            quoted_end(bytes, i, b'\'')
+        } else {
+            i += 1;
+            continue;
+        };
+        blank(i..end);
+        i = end;
+    }
+
+    // 只替换了完整的注释与字面量，结果仍是合法的 UTF-8
+    String::from_utf8(code).unwrap_or_default()
+}
+
+// 源码中所有 `#[cfg(test)]` 内联模块（从属性开始到右花括号为止）的字节范围
+pub fn test_modules(source: &str) -> Vec<Range<usize>> {
+    let code = blank_non_code(source);
+    let mut modules = Vec::new();
+    let mut from = 0;
+    while let Some(header) = TEST_MODULE.find_at(&code, from) {
+        let Some(end) = matching_brace(&code, header.end() - 1) else {
+            break;
+        };
+        modules.push(header.start()..end);
+        from = end;
+    }
+    modules
+}
+
+// 删除源码中所有的 `#[cfg(test)]` 内联模块
+pub fn strip_test_modules(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut last = 0;
+    for module in test_modules(source) {
+        stripped.push_str(&source[last..module.start]);
+        last = module.end;
+    }
+    stripped.push_str(&source[last..]);
+    stripped
+}
+
+// open 为左花括号的位置，返回与之匹配的右花括号之后的位置
+pub fn matching_brace(code: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (offset, byte) in code.as_bytes()[open..].iter().enumerate() {
+        match byte {
+            b'{' => depth += 1,
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(open + offset + 1);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// 块注释可以嵌套
+fn block_comment_end(bytes: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut i = start;
+    while i < bytes.len() {
+        if bytes[i..].starts_with(b"/*") {
+            depth += 1;
+            i += 2;
+        } else if bytes[i..].starts_with(b"*/") {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    bytes.len()
+}
+
+// r"..."、r#"..."#、br"..." 等原始字符串
+fn raw_string_end(bytes: &[u8], start: usize) -> Option<usize> {
+    // 标识符中间的 r（例如 `bar"`）不是原始字符串的开头
+    if start > 0 && (bytes[start - 1].is_ascii_alphanumeric() || bytes[start - 1] == b'_') {
+        return None;
+    }
+    let mut i = start;
+    if bytes.get(i) == Some(&b'b') {
+        i += 1;
+    }
+    if bytes.get(i) != Some(&b'r') {
+        return None;
+    }
+    i += 1;
+    let hashes = bytes[i..].iter().take_while(|&&byte| byte == b'#').count();
+    i += hashes;
+    if bytes.get(i) != Some(&b'"') {
+        return None;
+    }
+
+    let terminator: Vec<u8> = std::iter::once(b'"').chain(std::iter::repeat_n(b'#', hashes)).collect();
+    let body = i + 1;
+    Some(
+        bytes[body..]
+            .windows(terminator.len())
+            .position(|window| window == terminator)
+            .map_or(bytes.len(), |offset| body + offset + terminator.len()),
+    )
+}
+
+// 普通字符串与字符字面量，支持反斜杠转义
+fn quoted_end(bytes: &[u8], start: usize, quote: u8) -> usize {
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            byte if byte == quote => return i + 1,
+            _ => i += 1,
+        }
+    }
+    bytes.len()
+}
+
+// 区分字符字面量（'a'、'\n'、'中'）与生命周期（'a）
+fn is_char_literal(rest: &[u8]) -> bool {
+    if rest.get(1) == Some(&b'\\') {
+        return true;
+    }
+    // 跳过一个完整的 UTF-8 字符后应当是右引号
+    let width = match rest.get(1) {
+        Some(&byte) if byte < 0x80 => 1,
+        Some(&byte) if byte >= 0xF0 => 4,
+        Some(&byte) if byte >= 0xE0 => 3,
+        Some(_) => 2,
+        None => return false,
+    };
+    rest.get(1 + width) == Some(&b'\'')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blank_non_code_keeps_code_and_positions() {
+        let source = "let a = \"fn x() {\"; // fn y() {\n/* fn z() { */ let b = 'c';\n";
+        let code = blank_non_code(source);
+        assert_eq!(code.len(), source.len());
+        assert_eq!(code.matches('\n').count(), 2);
+        assert!(!code.contains("fn"));
+        assert!(!code.contains('{'));
+        assert!(code.contains("let a ="));
+        assert!(code.contains("let b ="));
+    }
+
+    #[test]
+    fn blank_non_code_handles_nested_comments_and_raw_strings() {
+        let code = blank_non_code("/* a /* b */ c */ x r#\"\"{\"# y br\"}\" z");
+        assert_eq!(code.split_whitespace().collect::<Vec<_>>(), ["x", "y", "z"]);
+    }
+
+    #[test]
+    fn blank_non_code_tells_lifetimes_from_chars() {
+        let code = blank_non_code("fn f<'a>(s: &'a str) -> char { '{' }");
+        assert!(code.contains("<'a>(s: &'a str)"));
+        assert_eq!(code.matches('{').count(), 1);
+        assert_eq!(code.matches('}').count(), 1);
+    }
+
+    #[test]
+    fn blank_non_code_keeps_multibyte_text_valid() {
+        let source = "let s = \"中文\"; // 注释\nlet c = '中';";
+        let code = blank_non_code(source);
+        assert_eq!(code.len(), source.len());
+        assert!(code.starts_with("let s ="));
+        assert!(code.contains("let c ="));
+    }
+
+    #[test]
+    fn test_modules_spans_attribute_to_closing_brace() {
+        let source = "fn a() {}\n#[cfg(test)]\n#[allow(unused)]\nmod tests {\n    fn b() { let s = \"}\"; }\n}\nfn c() {}\n";
+        let modules = test_modules(source);
+        assert_eq!(modules.len(), 1);
+        let module = &source[modules[0].clone()];
+        assert!(module.starts_with("#[cfg(test)]"));
+        assert!(module.ends_with("let s = \"}\"; }\n}"));
+    }
+
+    #[test]
+    fn test_modules_ignores_commented_out_and_non_test_modules() {
+        let source = "// #[cfg(test)] mod old {\n#[cfg(feature = \"x\")]\nmod x {}\nmod tests {}\n";
+        assert!(test_modules(source).is_empty());
+    }
+
+    #[test]
+    fn test_modules_finds_every_module() {
+        let source = "#[cfg(test)]\nmod a { }\nfn f() {}\n#[cfg(test)]\npub(crate) mod b { fn g() {} }\n";
+        let modules = test_modules(source);
+        assert_eq!(modules.len(), 2);
+        assert!(source[modules[1].clone()].contains("pub(crate) mod b"));
+        assert_eq!(strip_test_modules(source), "\nfn f() {}\n\n");
+    }
+}
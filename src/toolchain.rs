@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::process::Command;
+
+// 固定工具链的文件，与 rustup 相同，rust-toolchain.toml 优先
+const PIN_FILES: [&str; 2] = ["rust-toolchain.toml", "rust-toolchain"];
+
+// 评测题目需要的最低 rustc 版本：题目的 Cargo.lock 是第 4 版格式，cargo 1.78 起才能读取。
+// 评测器本身需要更新的版本（见 Cargo.toml 的 rust-version），但这里检查的是编译题目的工具链，
+// 它可以由 rust-toolchain 文件固定，与编译评测器的工具链不同
+const MIN_RUSTC: (u32, u32) = (1, 78);
+
+// 本次评测使用的工具链，写入报告
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Toolchain {
+    // `rustc -V` 的输出，例如 `rustc 1.95.0 (59807616e 2026-04-14)`
+    pub rustc: String,
+    // rustc 的版本号，nightly 与 beta 带有后缀，例如 `1.96.0-nightly`
+    pub release: String,
+    pub host: String,
+    // `cargo -V` 的输出
+    pub cargo: String,
+    // `cargo clippy -V` 的输出，没有安装 clippy 时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub clippy: Option<String>,
+    // rust-toolchain 文件中固定的工具链
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned: Option<String>,
+}
+
+impl Toolchain {
+    // 发布渠道：stable、beta 或 nightly
+    pub fn channel(&self) -> &'static str {
+        if self.release.contains("nightly") || self.release.contains("dev") {
+            "nightly"
+        } else if self.release.contains("beta") {
+            "beta"
+        } else {
+            "stable"
+        }
+    }
+
+    // 用于计算缓存键，工具链升级后缓存自动失效
+    pub fn fingerprint(&self) -> String {
+        format!("{}\n{}\n{}\n{}\n", self.rustc, self.host, self.cargo, self.clippy.as_deref().unwrap_or("no clippy"))
+    }
+
+    // 工具链中无法正常评测的问题，needs_clippy 表示选中的题目中有需要运行 clippy 的
+    pub fn problems(&self, needs_clippy: bool) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        match parse_version(&self.release) {
+            Some(version) if version < MIN_RUSTC => problems.push(format!(
+                "rustc {} is too old, at least {}.{} is required",
+                self.release, MIN_RUSTC.0, MIN_RUSTC.1
+            )),
+            Some(_) => {}
+            None => problems.push(format!("Cannot understand the rustc version '{}'", self.release)),
+        }
+
+        if let Some(pinned) = &self.pinned {
+            if !self.matches(pinned) {
+                problems.push(format!(
+                    "The toolchain is pinned to '{}' but rustc {} ({}) is active, install it with 'rustup toolchain install {}'",
+                    pinned,
+                    self.release,
+                    self.channel(),
+                    pinned
+                ));
+            }
+        }
+
+        if needs_clippy && self.clippy.is_none() {
+            problems.push("clippy is not installed, install it with 'rustup component add clippy'".to_string());
+        }
+
+        problems
+    }
+
+    // 当前的工具链是否符合固定的版本：版本号（例如 `1.80` 或 `1.80.1`）需与 rustc 的版本一致，
+    // 渠道名（例如 `stable`、`nightly-2026-01-01`）只比较渠道，不检查日期
+    fn matches(&self, pinned: &str) -> bool {
+        if pinned.starts_with(|c: char| c.is_ascii_digit()) {
+            let release = self.release.split('-').next().unwrap_or_default();
+            return release == pinned || release.starts_with(&format!("{}.", pinned));
+        }
+        pinned.split('-').next() == Some(self.channel())
+    }
+}
+
+// 仓库根目录中固定的工具链，没有固定时返回 None；文件中没有 channel 时返回错误
+pub fn pinned() -> Result<Option<String>, String> {
+    for file in PIN_FILES {
+        let Ok(content) = fs::read_to_string(file) else {
+            continue;
+        };
+
+        // toml 格式的 `channel = "1.80"`；旧格式的 rust-toolchain 只有一行工具链名称
+        let channel = content.lines().map(str::trim).find_map(|line| {
+            let value = line.strip_prefix("channel")?.trim_start().strip_prefix('=')?;
+            Some(value.trim().trim_matches(|c| c == '"' || c == '\'').to_string())
+        });
+        let legacy = || {
+            let line = content.lines().map(str::trim).find(|line| !line.is_empty() && !line.starts_with('#'))?;
+            (!line.contains(['=', '['])).then(|| line.to_string())
+        };
+        return match channel.or_else(legacy) {
+            Some(channel) if !channel.is_empty() => Ok(Some(channel)),
+            _ => Err(format!("{} does not specify a toolchain channel", file)),
+        };
+    }
+    Ok(None)
+}
+
+// 检测当前的工具链，rustc 或 cargo 无法运行时返回错误
+pub fn detect(pinned: Option<String>) -> Result<Toolchain, String> {
+    let verbose = version(Command::new("rustc").arg("-vV")).map_err(|e| format!("rustc is not available: {}", e))?;
+    let field = |name: &str| {
+        verbose.lines().find_map(|line| line.strip_prefix(name)).map(|value| value.trim().to_string())
+    };
+    let release = field("release:").ok_or("Cannot read the rustc version from 'rustc -vV'")?;
+    let host = field("host:").unwrap_or_default();
+    let rustc = verbose.lines().next().unwrap_or_default().to_string();
+    let cargo = version(Command::new("cargo").arg("-V")).map_err(|e| format!("cargo is not available: {}", e))?;
+    let clippy = version(Command::new("cargo").args(["clippy", "-V"])).ok();
+
+    Ok(Toolchain { rustc, release, host, cargo, clippy, pinned })
+}
+
+// 命令成功时返回其输出（去掉首尾空白），失败时返回 stderr 的第一行
+fn version(command: &mut Command) -> Result<String, String> {
+    let output = command.output().map_err(|e| e.to_string())?;
+    let text = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() && !text.is_empty() {
+        return Ok(text);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    Err(stderr.lines().find(|line| !line.trim().is_empty()).unwrap_or("no output").trim().to_string())
+}
+
+// 版本号的前两段，例如 `1.96.0-nightly` 为 (1, 96)
+fn parse_version(release: &str) -> Option<(u32, u32)> {
+    let mut parts = release.split(['.', '-']);
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+// 命令的输出表明缺少工具链组件（而不是题目本身有问题），例如没有安装 clippy
+pub fn missing_component(stderr: &[u8]) -> bool {
+    let stderr = String::from_utf8_lossy(stderr);
+    stderr.contains("no such command: `clippy`")
+        || stderr.contains("is not installed for the toolchain")
+        || stderr.contains("is not installed for toolchain")
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Component, Path};
+
+use colored::Colorize;
+
+use crate::hints::Hint;
+use crate::sandbox::Limits;
+use crate::scoring::ScoringPolicy;
+use crate::selection::Difficulty;
+use crate::{Exercise, ExerciseConfig};
+
+// 各难度题目的总分，与 README 中的说明保持一致
+const EXPECTED_TOTALS: [(Difficulty, i32); 3] = [(Difficulty::Easy, 20), (Difficulty::Normal, 30), (Difficulty::Hard, 50)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    // 无法正确评测，评测前必须修正
+    Error,
+    // 可以评测，但很可能是配置失误
+    Warning,
+}
+
+// 配置文件中的一个问题，location 形如 `normal[2] (solution3)`
+#[derive(Debug)]
+pub struct Problem {
+    pub severity: Severity,
+    pub location: String,
+    pub message: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error".red(),
+            Severity::Warning => "warning".yellow(),
+        };
+        write!(f, "{}: {}: {}", severity, self.location, self.message)
+    }
+}
+
+// 检查 exercise_config.json 中 serde 无法发现的问题：路径、重名、分值等
+pub fn validate(config: &ExerciseConfig) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut names: HashMap<&str, String> = HashMap::new();
+    let mut paths: HashMap<&str, String> = HashMap::new();
+
+    for (difficulty, exercises) in [
+        (Difficulty::Easy, &config.easy),
+        (Difficulty::Normal, &config.normal),
+        (Difficulty::Hard, &config.hard),
+    ] {
+        for (index, exercise) in exercises.iter().enumerate() {
+            let location = format!("{}[{}] ({})", difficulty, index, exercise.name);
+            let mut report = |severity, message: String| {
+                problems.push(Problem { severity, location: location.clone(), message });
+            };
+
+            if exercise.name.trim().is_empty() {
+                report(Severity::Error, "name must not be empty".to_string());
+            } else if let Some(first) = names.insert(&exercise.name, location.clone()) {
+                report(Severity::Error, format!("duplicate name, already used by {}", first));
+            }
+
+            match check_path(exercise) {
+                Err(message) => report(Severity::Error, message),
+                Ok(()) => {
+                    if let Some(first) = paths.insert(&exercise.path, location.clone()) {
+                        report(Severity::Warning, format!("path '{}' is also used by {}", exercise.path, first));
+                    }
+                }
+            }
+
+            if exercise.score <= 0 {
+                report(Severity::Error, format!("score must be positive, got {}", exercise.score));
+            }
+
+            if exercise.timeout == Some(0) {
+                report(Severity::Error, "timeout must be a positive number of seconds".to_string());
+            }
+
+            if let Some(message) = check_scoring(&exercise.scoring) {
+                report(Severity::Error, message);
+            }
+
+            if let Some(message) = exercise.limits.as_ref().and_then(check_limits) {
+                report(Severity::Error, message);
+            }
+
+            if let Some(message) = exercise.clippy.check() {
+                report(Severity::Error, message);
+            }
+            if !exercise.clippy.is_default() && !exercise.kind.evaluator().uses_clippy() {
+                report(Severity::Warning, format!("clippy has no effect, {} does not run clippy", exercise.kind.name()));
+            }
+
+            if let Some(Err(message)) = exercise.hint.as_ref().map(Hint::stages) {
+                report(Severity::Error, format!("hint: {}", message));
+            }
+        }
+    }
+
+    for (difficulty, expected) in EXPECTED_TOTALS {
+        let total: i32 = config
+            .exercises()
+            .filter(|(exercise_difficulty, _)| *exercise_difficulty == difficulty)
+            .map(|(_, exercise)| exercise.score)
+            .sum();
+        if total != expected {
+            problems.push(Problem {
+                severity: Severity::Warning,
+                location: difficulty.to_string(),
+                message: format!("scores add up to {}, expected {}", total, expected),
+            });
+        }
+    }
+
+    problems
+}
+
+// 路径相对于 exercises/ 目录，其余的要求（以及该类型特有的配置）由题目类型检查
+fn check_path(exercise: &Exercise) -> Result<(), String> {
+    let relative = Path::new(&exercise.path);
+    if exercise.path.is_empty() || !relative.components().all(|component| matches!(component, Component::Normal(_))) {
+        return Err(format!("path '{}' must be a relative path inside exercises/", exercise.path));
+    }
+
+    exercise
+        .kind
+        .evaluator()
+        .check(&exercise.source_path())
+        .map_err(|message| format!("{}: {}", exercise.kind.name(), message))
+}
+
+fn check_scoring(scoring: &ScoringPolicy) -> Option<String> {
+    let ScoringPolicy::Weighted { weights } = scoring else {
+        return None;
+    };
+
+    if weights.is_empty() {
+        return Some("weighted scoring needs at least one test weight".to_string());
+    }
+    if let Some((name, weight)) = weights.iter().find(|(_, weight)| !weight.is_finite() || **weight < 0.0) {
+        return Some(format!("weight of test '{}' must be a non-negative number, got {}", name, weight));
+    }
+    if weights.values().sum::<f64>() <= 0.0 {
+        return Some("weights must not all be zero".to_string());
+    }
+    None
+}
+
+fn check_limits(limits: &Limits) -> Option<String> {
+    [
+        ("cpu_secs", limits.cpu_secs),
+        ("memory_mb", limits.memory_mb),
+        ("file_size_mb", limits.file_size_mb),
+        ("processes", limits.processes),
+    ]
+    .into_iter()
+    .find(|(_, value)| *value == 0)
+    .map(|(field, _)| format!("limits.{} must be positive", field))
+}
@@ -0,0 +1,244 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use colored::Colorize;
+use log::Level;
+use termion::event::Key;
+use termion::input::TermRead;
+use termion::raw::IntoRawMode;
+use termion::{clear, cursor};
+
+use crate::files::project_files;
+use crate::history::{self, HISTORY_FILE};
+use crate::logging;
+use crate::{Evaluation, Exercise, Grader, Outcome, hints};
+
+// 轮询题目文件修改时间的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+// 每道题最近一次的评测结果，以及自最近一次通过以来未通过的次数（包括历史记录），用于解锁提示
+struct State {
+    results: Vec<Option<Evaluation>>,
+    failures: Vec<usize>,
+}
+
+enum Event {
+    Key(Key),
+    // 第 n 道题的源文件发生了变化
+    Changed(usize),
+}
+
+// 监视 exercises 目录，题目文件保存后自动重新评测该题
+//
+// 与 rustlings 类似，始终停留在第一道未通过（包括仍带有 `// I AM NOT DONE` 标记）的题目上；按键：r 重新评测当前题目，
+// n 跳到下一题，h 显示当前题目的提示，q 退出。返回每道题最近一次的评测结果。
+pub fn watch(grader: &Grader, exercises: &[Exercise]) -> io::Result<Vec<Option<Evaluation>>> {
+    if !termion::is_tty(&io::stdin()) {
+        return Err(io::Error::other("watch mode needs an interactive terminal"));
+    }
+
+    let (sender, events) = mpsc::channel();
+    spawn_key_reader(sender.clone());
+    spawn_file_watcher(sender, exercises.iter().map(|exercise| exercise.source_path()).collect());
+
+    let runs = history::load(HISTORY_FILE).unwrap_or_default();
+    let mut state = State {
+        results: exercises.iter().map(|_| None).collect(),
+        failures: exercises.iter().map(|exercise| history::failures_since_pass(&runs, &exercise.name)).collect(),
+    };
+
+    // 评测输出显示在界面中，同时写入日志文件
+    let _console = logging::suspend_console();
+    let mut screen = io::stdout().into_raw_mode()?;
+    let mut current = 0;
+    let mut message = Vec::new();
+
+    // 从第一题开始依次评测，直到遇到未通过的题目
+    advance(grader, exercises, &mut state, &mut current, &mut message, &mut screen, false)?;
+
+    while let Ok(event) = events.recv() {
+        match event {
+            Event::Key(Key::Char('q')) | Event::Key(Key::Ctrl('c')) => break,
+            Event::Key(Key::Char('r')) => {
+                advance(grader, exercises, &mut state, &mut current, &mut message, &mut screen, true)?;
+            }
+            Event::Key(Key::Char('n')) => {
+                current = (current + 1) % exercises.len();
+                advance(grader, exercises, &mut state, &mut current, &mut message, &mut screen, false)?;
+            }
+            Event::Key(Key::Char('h')) => {
+                message = hints::lines(&exercises[current], state.failures[current]);
+                render(&mut screen, exercises, &state.results, current, &message)?;
+            }
+            Event::Key(_) => {}
+            Event::Changed(index) => {
+                // 当前题目已经通过（例如所有题目都已完成）时，转到刚修改的题目
+                if index == current || passed(&state.results[current]) {
+                    current = index;
+                    advance(grader, exercises, &mut state, &mut current, &mut message, &mut screen, false)?;
+                } else {
+                    message = evaluate(grader, exercises, &mut state, index, current, &mut screen, false)?;
+                    render(&mut screen, exercises, &state.results, current, &message)?;
+                }
+            }
+        }
+    }
+
+    write!(screen, "\r\n")?;
+    Ok(state.results)
+}
+
+// 评测当前题目，通过后继续评测后面尚未通过的题目，停在第一道未通过的题目上；
+// fresh 为 true 时当前题目忽略缓存重新评测
+fn advance(
+    grader: &Grader,
+    exercises: &[Exercise],
+    state: &mut State,
+    current: &mut usize,
+    message: &mut Vec<String>,
+    screen: &mut impl Write,
+    mut fresh: bool,
+) -> io::Result<()> {
+    loop {
+        *message = evaluate(grader, exercises, state, *current, *current, screen, fresh)?;
+        fresh = false;
+
+        if !passed(&state.results[*current]) {
+            break;
+        }
+        match (0..exercises.len()).map(|offset| (*current + offset) % exercises.len()).find(|&i| !passed(&state.results[i])) {
+            Some(next) => *current = next,
+            None => {
+                message.push(String::new());
+                message.push("All exercises passed!".green().to_string());
+                break;
+            }
+        }
+    }
+
+    render(screen, exercises, &state.results, *current, message)
+}
+
+// 评测一道题并记录结果，返回评测过程的输出
+fn evaluate(
+    grader: &Grader,
+    exercises: &[Exercise],
+    state: &mut State,
+    index: usize,
+    current: usize,
+    screen: &mut impl Write,
+    fresh: bool,
+) -> io::Result<Vec<String>> {
+    let status = vec![format!("Evaluating {}...", exercises[index].name)];
+    render(screen, exercises, &state.results, current, &status)?;
+
+    let exercise = &exercises[index];
+    let (evaluation, output) = if fresh { grader.reevaluate(exercise) } else { grader.evaluate(exercise) };
+    output.print();
+    // 还没有删除完成标记与环境问题都不算本题的一次失败，不影响提示的解锁
+    state.failures[index] = match evaluation.outcome {
+        Outcome::Passed => 0,
+        Outcome::InProgress | Outcome::EnvironmentError => state.failures[index],
+        _ => state.failures[index] + 1,
+    };
+    state.results[index] = Some(evaluation);
+
+    // 调试信息（例如完整的编译器输出）只写入日志文件
+    Ok(output
+        .lines
+        .into_iter()
+        .filter(|(level, _)| *level <= Level::Info)
+        .flat_map(|(_, line)| line.lines().map(String::from).collect::<Vec<_>>())
+        .collect())
+}
+
+// 重绘整个界面：进度条、当前题目、评测输出与按键说明
+fn render(
+    screen: &mut impl Write,
+    exercises: &[Exercise],
+    results: &[Option<Evaluation>],
+    current: usize,
+    message: &[String],
+) -> io::Result<()> {
+    const BAR_WIDTH: usize = 40;
+
+    let passed = results.iter().filter(|result| passed(result)).count();
+    let in_progress = results
+        .iter()
+        .filter(|result| matches!(result, Some(evaluation) if evaluation.outcome == Outcome::InProgress))
+        .count();
+    let filled = passed * BAR_WIDTH / exercises.len().max(1);
+
+    write!(screen, "{}{}", clear::All, cursor::Goto(1, 1))?;
+    let progress = format!(
+        "Progress: [{}{}] {}/{} ({} in progress)",
+        "#".repeat(filled).green(),
+        "-".repeat(BAR_WIDTH - filled),
+        passed,
+        exercises.len(),
+        in_progress
+    );
+    write!(screen, "{}\r\n", logging::paint(&progress))?;
+    write!(screen, "Current exercise: {}\r\n", exercises[current].name)?;
+
+    for line in message {
+        write!(screen, "{}\r\n", logging::paint(line))?;
+    }
+
+    write!(screen, "\r\n[r] re-run  [n] next exercise  [h] hint  [q] quit\r\n")?;
+    screen.flush()
+}
+
+fn passed(result: &Option<Evaluation>) -> bool {
+    matches!(result, Some(evaluation) if evaluation.outcome == Outcome::Passed)
+}
+
+fn spawn_key_reader(sender: Sender<Event>) {
+    thread::spawn(move || {
+        for key in io::stdin().keys().map_while(Result::ok) {
+            if sender.send(Event::Key(key)).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+// 定期检查每道题的源文件，发现修改后通知主循环
+fn spawn_file_watcher(sender: Sender<Event>, paths: Vec<PathBuf>) {
+    thread::spawn(move || {
+        let mut fingerprints: Vec<_> = paths.iter().map(|path| fingerprint(path)).collect();
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+
+            for (index, path) in paths.iter().enumerate() {
+                let latest = fingerprint(path);
+                if latest != fingerprints[index] {
+                    fingerprints[index] = latest;
+                    if sender.send(Event::Changed(index)).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+// 题目中所有源文件（.rs 与 Cargo.toml）的修改时间，跳过评测时生成的 target 目录
+fn fingerprint(path: &Path) -> Vec<(PathBuf, Option<SystemTime>)> {
+    // 无法读取的目录（例如评测过程中被删除）当作没有文件
+    project_files(path)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|file| path.is_file() || file.extension().is_some_and(|ext| ext == "rs") || file.ends_with("Cargo.toml"))
+        .map(|file| {
+            let modified = fs::metadata(&file).and_then(|metadata| metadata.modified()).ok();
+            (file, modified)
+        })
+        .collect()
+}
+
@@ -0,0 +1,79 @@
+/*
+    Rotate Matrix 90 Degrees
+    Given a 2D matrix, rotate it 90 degrees in place. 
+    You need to perform the rotation without using any additional matrix storage.
+
+    You need to implement the function `rotate_matrix_90_degrees(matrix: &mut Vec<Vec<i32>>)`.
+    The function should rotate the input matrix in place.
+
+    Hint: Consider rotating the matrix layer by layer, starting from the outermost layer and working your way inward.
+*/
+// I AM NOT DONE
+
+use std::fmt::{self, Display, Formatter};
+
+pub fn rotate_matrix_90_degrees(matrix: &mut Vec<Vec<i32>>) {
+    // TODO
+    todo!()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate_matrix_1() {
+        let mut matrix = vec![
+            vec![1, 2, 3],
+            vec![4, 5, 6],
+            vec![7, 8, 9],
+        ];
+        rotate_matrix_90_degrees(&mut matrix);
+        println!("Rotated matrix: {:?}", matrix);
+        assert_eq!(matrix, vec![
+            vec![7, 4, 1],
+            vec![8, 5, 2],
+            vec![9, 6, 3],
+        ]);
+    }
+
+    #[test]
+    fn test_rotate_matrix_2() {
+        let mut matrix = vec![
+            vec![1, 2],
+            vec![3, 4],
+        ];
+        rotate_matrix_90_degrees(&mut matrix);
+        println!("Rotated matrix: {:?}", matrix);
+        assert_eq!(matrix, vec![
+            vec![3, 1],
+            vec![4, 2],
+        ]);
+    }
+
+    #[test]
+    fn test_rotate_matrix_3() {
+        let mut matrix = vec![
+            vec![1],
+        ];
+        rotate_matrix_90_degrees(&mut matrix);
+        println!("Rotated matrix: {:?}", matrix);
+        assert_eq!(matrix, vec![
+            vec![1],
+        ]);
+    }
+
+    #[test]
+    fn test_rotate_matrix_4() {
+        let mut matrix = vec![
+            vec![1, 2],
+            vec![3, 4],
+            vec![5, 6],
+        ];
+        rotate_matrix_90_degrees(&mut matrix);
+        println!("Rotated matrix: {:?}", matrix);
+        assert_eq!(matrix, vec![
+            vec![5, 3, 1],
+            vec![6, 4, 2],
+        ]);
+    }
+}
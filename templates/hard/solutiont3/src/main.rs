@@ -0,0 +1,8 @@
+// I AM NOT DONE
+
+mod district;
+
+fn main() {
+    let provinces = district::count_provinces();
+    println!("provinces: {provinces}");
+}
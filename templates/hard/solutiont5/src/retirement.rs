@@ -0,0 +1,4 @@
+pub fn retire_time(birth: &str, personnel: &str) -> String {
+    // TODO
+    todo!()
+}
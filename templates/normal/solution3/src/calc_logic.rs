@@ -0,0 +1,4 @@
+pub fn new_birthday_probability(n: u32) -> f64 {
+    // TODO
+    todo!()
+}